@@ -1,3 +1,5 @@
+extern crate bignum;
+use bignum::BigRational;
 use std::env;
 
 #[derive(Copy, Clone)]
@@ -34,7 +36,7 @@ impl TempScale {
     }
 }
 
-fn parse_source(s: &String) -> Option<(TempScale, f32)> {
+fn parse_source(s: &String) -> Option<(TempScale, BigRational)> {
     let mut s = s.clone();
     let last = match s.pop() {
         Some(c) => c,
@@ -49,32 +51,36 @@ fn parse_source(s: &String) -> Option<(TempScale, f32)> {
         }
     };
     let number =
-        match s.parse::<f32>() {
+        match s.parse::<BigRational>() {
             Ok(c) => c,
             Err(_) => return None,
         };
     return Some((scale, number));
 }
 
-fn to_kelvin(scale: TempScale, val: f32) -> f32 {
+fn num(s: &str) -> BigRational {
+    s.parse().unwrap()
+}
+
+fn to_kelvin(scale: TempScale, val: BigRational) -> BigRational {
     match scale {
         TempScale::Kelvin => val,
-        TempScale::Celsius => val + 273.15,
-        TempScale::Fahrenheit => (val + 459.67) * 5.0 / 9.0,
-        TempScale::Rankine => val * 5.0 / 9.0,
+        TempScale::Celsius => val + num("273.15"),
+        TempScale::Fahrenheit => (val + num("459.67")) * num("5/9"),
+        TempScale::Rankine => val * num("5/9"),
     }
 }
 
-fn from_kelvin(scale: TempScale, val: f32) -> f32 {
+fn from_kelvin(scale: TempScale, val: BigRational) -> BigRational {
     match scale {
         TempScale::Kelvin => val,
-        TempScale::Celsius => val - 273.15,
-        TempScale::Fahrenheit => val * 9.0 / 5.0 - 459.67,
-        TempScale::Rankine => val * 9.0 / 5.0,
+        TempScale::Celsius => val - num("273.15"),
+        TempScale::Fahrenheit => val * num("9/5") - num("459.67"),
+        TempScale::Rankine => val * num("9/5"),
     }
 }
 
-fn convert_temp(from: TempScale, to: TempScale, val: f32) -> f32 {
+fn convert_temp(from: TempScale, to: TempScale, val: BigRational) -> BigRational {
     from_kelvin(to, to_kelvin(from, val))
 }
 
@@ -102,7 +108,7 @@ fn main() {
     match parse_source(&args[0]) {
         Some((src_scale, number)) => {
             println!("from {} {}",
-                     number, src_scale.to_string());
+                     number.to_f64(), src_scale.to_string());
             let answer = convert_temp(src_scale, to_scale, number);
             println!("to {:.2} {}",
                      answer, to_scale.to_string());
@@ -1,16 +1,13 @@
 extern crate bignum;
 use bignum::{BigUint};
+use std::mem;
 
 fn main() {
     let mut a = BigUint::from_u64(1);
     let mut b = BigUint::from_u64(1);
     for _ in 0..200 {
-        let next = a.add(&b);
-        match a.to_base_string(10) {
-            Ok(s) => { println!("{}", s); },
-            Err(err) => { panic!(err); },
-        };
-        a = b;
-        b = next;
+        println!("{}", a);
+        a.add_assign(&b);
+        mem::swap(&mut a, &mut b);
     }
 }
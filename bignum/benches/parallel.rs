@@ -0,0 +1,67 @@
+// Serial against parallel timings on huge operands. Run with
+// cargo bench --features parallel; BIGNUM_THREADS overrides the number
+// of threads, which otherwise is one per core
+extern crate bignum;
+use bignum::{BigUint,Xoshiro256};
+
+use std::time::{Duration,Instant};
+
+// the best of a few runs, and the result to check against the other path
+fn time<T, F: FnMut() -> T>(mut f: F) -> (Duration, T) {
+    let mut best = Duration::MAX;
+    let mut result = None;
+    for _ in 0..3 {
+        let start = Instant::now();
+        let r = f();
+        best = best.min(start.elapsed());
+        result = Some(r);
+    }
+    (best, result.unwrap())
+}
+
+fn compare<T: PartialEq, F: FnMut() -> T>(name: &str, mut f: F) {
+    let threads = bignum::max_threads();
+    bignum::set_max_threads(1);
+    let (serial, expected) = time(&mut f);
+    bignum::set_max_threads(threads);
+    let (parallel, result) = time(&mut f);
+    assert!(result == expected, "{}: the parallel result differs", name);
+    println!("{:<28} serial {:>10.2?}  {} threads {:>10.2?}  x{:.2}",
+             name, serial, threads, parallel, serial.as_secs_f64() / parallel.as_secs_f64());
+}
+
+// the products right around PAR_THRESHOLD (1024 limbs), next to the cost
+// of spawning a thread for every split, which the pool saves
+fn near_threshold() {
+    let (spawn, _) = time(|| std::thread::scope(|s| s.spawn(|| ()).join().unwrap()));
+    println!("spawning and joining a scoped thread: {:.2?}", spawn);
+    let mut rng = Xoshiro256::new(0x9E3779B97F4A7C15);
+    for &limbs in &[512, 1024, 1536, 2048, 4096] {
+        let a = BigUint::random_bits(64 * limbs, &mut rng);
+        let b = BigUint::random_bits(64 * limbs, &mut rng);
+        compare(&format!("{} x {} limbs", limbs, limbs), || &a * &b);
+        compare(&format!("{} limbs squared", limbs), || a.square());
+    }
+}
+
+fn main() {
+    if let Some(n) = std::env::var("BIGNUM_THREADS").ok().and_then(|n| n.parse().ok()) {
+        bignum::set_max_threads(n);
+    }
+    let fact = BigUint::factorial(100_000);
+    let fib = BigUint::fibonacci(1_000_000);
+    let fact_digits = fact.to_string();
+    let fib_digits = fib.to_string();
+    println!("fact(100000): {} digits, fib(10^6): {} digits", fact_digits.len(), fib_digits.len());
+
+    compare("factorial(100000)", || BigUint::factorial(100_000));
+    compare("fibonacci(10^6)", || BigUint::fibonacci(1_000_000));
+    compare("fact(100000)^2", || fact.square());
+    compare("fact(100000) * fib(10^6)", || &fact * &fib);
+    compare("fact(100000) to decimal", || fact.to_string());
+    compare("fib(10^6) to decimal", || fib.to_string());
+    compare("fact(100000) from decimal", || BigUint::from_base_string(&fact_digits, 10).unwrap());
+    compare("fib(10^6) from decimal", || BigUint::from_base_string(&fib_digits, 10).unwrap());
+
+    near_threshold();
+}
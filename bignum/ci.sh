@@ -0,0 +1,14 @@
+#!/bin/sh
+# runs the checks for the portable build and for each optional feature set
+set -ex
+cd "$(dirname "$0")"
+
+for features in "" "asm" "asm,serde,parallel"; do
+    cargo build --features "$features"
+    cargo clippy --all-targets --features "$features" -- -D warnings
+    cargo test --features "$features"
+done
+
+# the timing tests in tests/ct.rs are ignored by default and only
+# meaningful in a release build
+cargo test --release --test ct -- --ignored
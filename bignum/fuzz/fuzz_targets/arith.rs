@@ -0,0 +1,13 @@
+// BigUint arithmetic against the slow reference and u128, with
+// `cargo fuzz run arith`. Crashes land in artifacts/arith; copy them to
+// regressions/arith, where tests/fuzz_regressions.rs replays them on
+// every `cargo test`.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+#[path = "../../tests/common/reference.rs"]
+mod reference;
+
+fuzz_target!(|data: &[u8]| reference::check_arith(data));
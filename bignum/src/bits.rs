@@ -0,0 +1,87 @@
+// Bit access and bitwise operations. BigInt behaves as an infinitely
+// sign-extended two's complement number, the way Python and GMP do, so
+// -1 has every bit set and -x == !x + 1.
+
+use super::{BigUint,BigInt,IntSign,BITS_IN_LIMB};
+use limbs;
+
+impl BigUint {
+    pub fn test_bit(&self, pos: usize) -> bool {
+        let limb = pos / BITS_IN_LIMB;
+        limb < self.limbs.len() && (self.limbs[limb] >> (pos % BITS_IN_LIMB)) & 1 == 1
+    }
+
+    pub fn clear_bit(&mut self, pos: usize) {
+        let limb = pos / BITS_IN_LIMB;
+        if limb < self.limbs.len() {
+            self.limbs[limb] &= !(1 << (pos % BITS_IN_LIMB));
+            self.trim();
+        }
+    }
+
+    // number of set bits
+    pub fn count_ones(&self) -> usize {
+        self.limbs.iter().map(|x| x.count_ones() as usize).sum()
+    }
+}
+
+impl BigInt {
+    // bit of the two's complement representation
+    pub fn test_bit(&self, pos: usize) -> bool {
+        if !self.is_negative() {
+            return self.num.test_bit(pos);
+        }
+        // -m == !(m - 1)
+        !(&self.num - 1).test_bit(pos)
+    }
+}
+
+// combines the limbs one by one, padding the shorter operand with zeros
+pub fn zip_biguint<F>(a: &BigUint, b: &BigUint, f: F) -> BigUint
+    where F: Fn(u64, u64) -> u64 {
+    let len = a.limbs.len().max(b.limbs.len());
+    let limb = |n: &BigUint, i: usize| n.limbs.get(i).cloned().unwrap_or(0);
+    BigUint::from_limbs((0..len).map(|i| f(limb(a, i), limb(b, i))).collect())
+}
+
+// the same in two's complement; one extra limb holds the sign extension
+pub fn zip_bigint<F>(a: &BigInt, b: &BigInt, f: F) -> BigInt
+    where F: Fn(u64, u64) -> u64 {
+    let len = a.num.limbs.len().max(b.num.limbs.len()) + 1;
+    let (ta, tb) = (twos_complement(a, len), twos_complement(b, len));
+    let mut result: Vec<u64> = ta.iter().zip(tb.iter()).map(|(&x, &y)| f(x, y)).collect();
+
+    let negative = result[len - 1] >> 63 == 1;
+    if negative {
+        limbs::negate(&mut result);
+    }
+    let sign = if negative { IntSign::Neg } else { IntSign::Pos };
+    BigInt::from_biguint(sign, BigUint::from_limbs(result))
+}
+
+fn twos_complement(n: &BigInt, len: usize) -> Vec<u64> {
+    let mut result = n.num.limbs.clone();
+    result.resize(len, 0);
+    if n.is_negative() {
+        limbs::negate(&mut result);
+    }
+    result
+}
+
+// !n == -n - 1
+pub fn not_bigint(n: &BigInt) -> BigInt {
+    if n.is_negative() {
+        BigInt::from_biguint(IntSign::Pos, &n.num - 1)
+    } else {
+        BigInt::from_biguint(IntSign::Neg, &n.num + 1)
+    }
+}
+
+// floor(n / 2^shift), which for negative n rounds towards -infinity
+pub fn shr_bigint(n: &BigInt, shift: usize) -> BigInt {
+    if !n.is_negative() {
+        return BigInt::from_biguint(IntSign::Pos, &n.num >> shift);
+    }
+    // -m >> s == -(((m - 1) >> s) + 1)
+    BigInt::from_biguint(IntSign::Neg, ((&n.num - 1) >> shift) + 1)
+}
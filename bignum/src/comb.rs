@@ -0,0 +1,144 @@
+// Combinatorics and integer sequences. Factorials and binomials are
+// built from their prime factorizations and multiplied out with a
+// balanced product tree, so the big multiplications happen between
+// operands of similar size, where the fast algorithms pay off.
+
+use std::cmp::min;
+
+use super::BigUint;
+use prime::sieve;
+
+// the product of the factors, u64-sized leaves first
+fn product(factors: &[u64]) -> BigUint {
+    let mut leaves = Vec::new();
+    let mut acc = 1u64;
+    for &f in factors {
+        match acc.checked_mul(f) {
+            Some(v) => acc = v,
+            None => {
+                leaves.push(BigUint::from_u64(acc));
+                acc = f;
+            }
+        }
+    }
+    leaves.push(BigUint::from_u64(acc));
+    product_tree(&leaves)
+}
+
+fn product_tree(leaves: &[BigUint]) -> BigUint {
+    match leaves.len() {
+        1 => leaves[0].clone(),
+        2 => &leaves[0] * &leaves[1],
+        len => {
+            let (lo, hi) = leaves.split_at(len / 2);
+            &product_tree(lo) * &product_tree(hi)
+        }
+    }
+}
+
+fn primes_up_to(n: u64) -> Vec<u64> {
+    sieve(n as usize + 1)
+}
+
+// the odd part of the swinging factorial n! / (n/2)!^2: p appears once
+// for every odd digit in floor(n / p^i)
+fn odd_swing(n: u64, primes: &[u64]) -> BigUint {
+    let mut factors = Vec::new();
+    for &p in primes.iter().skip(1).take_while(|&&p| p <= n) {
+        let mut q = n;
+        while q >= p {
+            q /= p;
+            if q & 1 == 1 {
+                factors.push(p);
+            }
+        }
+    }
+    product(&factors)
+}
+
+// n! with the factors of two removed
+fn odd_factorial(n: u64, primes: &[u64]) -> BigUint {
+    if n < 2 {
+        return BigUint::from_u64(1);
+    }
+    &odd_factorial(n / 2, primes).square() * &odd_swing(n, primes)
+}
+
+// (F(n), F(n + 1)) by fast doubling:
+// F(2k) = F(k) * (2F(k + 1) - F(k)), F(2k + 1) = F(k)^2 + F(k + 1)^2
+fn fibonacci_pair(n: u64) -> (BigUint, BigUint) {
+    let (mut a, mut b) = (BigUint::from_u64(0), BigUint::from_u64(1));
+    for i in (0..64 - n.leading_zeros()).rev() {
+        let c = &a * &(&(&b << 1) - &a);
+        let d = &a.square() + &b.square();
+        if (n >> i) & 1 == 1 {
+            a = d;
+            b = &a + &c;
+        } else {
+            a = c;
+            b = d;
+        }
+    }
+    (a, b)
+}
+
+impl BigUint {
+    // n!, by Luschny's prime swing: n! = (n/2)!^2 * swing(n)
+    pub fn factorial(n: u64) -> BigUint {
+        let primes = primes_up_to(n);
+        odd_factorial(n, &primes) << (n - n.count_ones() as u64) as usize
+    }
+
+    // n choose k; zero for k > n
+    pub fn binomial(n: u64, k: u64) -> BigUint {
+        if k > n {
+            return BigUint::from_u64(0);
+        }
+        let k = min(k, n - k);
+        // for small k, n! / (n - k)! / k! is far cheaper than sieving up to n
+        if k <= n / 64 {
+            let top: Vec<u64> = (0..k).map(|i| n - i).collect();
+            return product(&top).divmod(&BigUint::factorial(k)).0;
+        }
+        // by Kummer, p appears once for every borrow when subtracting
+        // k from n in base p
+        let mut factors = Vec::new();
+        for p in primes_up_to(n) {
+            let (mut n, mut k, mut borrow) = (n, k, 0);
+            while n > 0 {
+                let digit = (n % p) as i64 - (k % p) as i64 - borrow;
+                borrow = (digit < 0) as i64;
+                if borrow == 1 {
+                    factors.push(p);
+                }
+                n /= p;
+                k /= p;
+            }
+        }
+        product(&factors)
+    }
+
+    // F(0) = 0, F(1) = 1
+    pub fn fibonacci(n: u64) -> BigUint {
+        fibonacci_pair(n).0
+    }
+
+    // L(0) = 2, L(1) = 1, and L(n) = 2F(n + 1) - F(n)
+    pub fn lucas(n: u64) -> BigUint {
+        let (f, f1) = fibonacci_pair(n);
+        &(f1 << 1) - &f
+    }
+
+    // the product of the primes <= n
+    pub fn primorial(n: u64) -> BigUint {
+        product(&primes_up_to(n))
+    }
+
+    // C(2n, n) / (n + 1); panics if 2n overflows a u64
+    pub fn catalan(n: u64) -> BigUint {
+        let two_n = n.checked_mul(2).expect("attempt to compute a Catalan number with overflow");
+        let (q, r) = BigUint::binomial(two_n, n).divmod_limb(n + 1);
+        debug_assert_eq!(r, 0);
+        q
+    }
+}
@@ -0,0 +1,346 @@
+// Conversions to and from primitive integers, floats and bytes. All of
+// them are lossless except for floats, which round to nearest.
+
+use std::convert::TryFrom;
+
+use super::{BigUint,BigInt,IntSign,BigNumError};
+
+impl From<u128> for BigUint {
+    fn from(n: u128) -> BigUint {
+        BigUint::from_limbs(vec![n as u64, (n >> 64) as u64])
+    }
+}
+
+impl From<i128> for BigInt {
+    fn from(n: i128) -> BigInt {
+        let sign = if n < 0 { IntSign::Neg } else { IntSign::Pos };
+        BigInt::from_biguint(sign, BigUint::from(n.unsigned_abs()))
+    }
+}
+
+impl From<BigUint> for BigInt {
+    fn from(n: BigUint) -> BigInt {
+        BigInt::from_biguint(IntSign::Pos, n)
+    }
+}
+
+impl TryFrom<&BigUint> for u128 {
+    type Error = BigNumError;
+
+    fn try_from(n: &BigUint) -> Result<u128, BigNumError> {
+        match *n.limbs() {
+            [lo] => Ok(lo as u128),
+            [lo, hi] => Ok(((hi as u128) << 64) | lo as u128),
+            _ => Err(BigNumError::OutOfRange),
+        }
+    }
+}
+
+impl TryFrom<&BigInt> for i128 {
+    type Error = BigNumError;
+
+    fn try_from(n: &BigInt) -> Result<i128, BigNumError> {
+        let m = u128::try_from(&n.num)?;
+        if n.is_negative() {
+            // -2^127 is the one magnitude that only fits negated
+            if m > 1 << 127 {
+                return Err(BigNumError::OutOfRange);
+            }
+            Ok((m as i128).wrapping_neg())
+        } else {
+            i128::try_from(m).map_err(|_| BigNumError::OutOfRange)
+        }
+    }
+}
+
+impl TryFrom<&BigInt> for BigUint {
+    type Error = BigNumError;
+
+    fn try_from(n: &BigInt) -> Result<BigUint, BigNumError> {
+        if n.is_negative() {
+            return Err(BigNumError::Negative);
+        }
+        Ok(n.num.clone())
+    }
+}
+
+impl From<u128> for BigInt {
+    fn from(n: u128) -> BigInt {
+        BigInt::from(BigUint::from(n))
+    }
+}
+
+impl TryFrom<BigUint> for u128 {
+    type Error = BigNumError;
+
+    fn try_from(n: BigUint) -> Result<u128, BigNumError> {
+        u128::try_from(&n)
+    }
+}
+
+impl TryFrom<&BigUint> for i128 {
+    type Error = BigNumError;
+
+    fn try_from(n: &BigUint) -> Result<i128, BigNumError> {
+        let n = u128::try_from(n)?;
+        i128::try_from(n).map_err(|_| BigNumError::OutOfRange)
+    }
+}
+
+impl TryFrom<BigUint> for i128 {
+    type Error = BigNumError;
+
+    fn try_from(n: BigUint) -> Result<i128, BigNumError> {
+        i128::try_from(&n)
+    }
+}
+
+impl TryFrom<&BigInt> for u128 {
+    type Error = BigNumError;
+
+    fn try_from(n: &BigInt) -> Result<u128, BigNumError> {
+        if n.is_negative() {
+            return Err(BigNumError::Negative);
+        }
+        u128::try_from(&n.num)
+    }
+}
+
+impl TryFrom<BigInt> for u128 {
+    type Error = BigNumError;
+
+    fn try_from(n: BigInt) -> Result<u128, BigNumError> {
+        u128::try_from(&n)
+    }
+}
+
+impl TryFrom<BigInt> for i128 {
+    type Error = BigNumError;
+
+    fn try_from(n: BigInt) -> Result<i128, BigNumError> {
+        i128::try_from(&n)
+    }
+}
+
+impl TryFrom<BigInt> for BigUint {
+    type Error = BigNumError;
+
+    fn try_from(n: BigInt) -> Result<BigUint, BigNumError> {
+        if n.is_negative() {
+            return Err(BigNumError::Negative);
+        }
+        Ok(n.num)
+    }
+}
+
+// the smaller primitives go through u128 and i128
+macro_rules! from_unsigned {
+    ($($t:ty),*) => {$(
+        impl From<$t> for BigUint {
+            fn from(n: $t) -> BigUint {
+                BigUint::from(n as u128)
+            }
+        }
+
+        impl From<$t> for BigInt {
+            fn from(n: $t) -> BigInt {
+                BigInt::from(n as i128)
+            }
+        }
+    )*}
+}
+
+macro_rules! from_signed {
+    ($($t:ty),*) => {$(
+        impl From<$t> for BigInt {
+            fn from(n: $t) -> BigInt {
+                BigInt::from(n as i128)
+            }
+        }
+    )*}
+}
+
+macro_rules! try_into_primitive {
+    ($($t:ty),*) => {$(
+        impl TryFrom<&BigUint> for $t {
+            type Error = BigNumError;
+
+            fn try_from(n: &BigUint) -> Result<$t, BigNumError> {
+                let n = u128::try_from(n)?;
+                <$t>::try_from(n).map_err(|_| BigNumError::OutOfRange)
+            }
+        }
+
+        impl TryFrom<BigUint> for $t {
+            type Error = BigNumError;
+
+            fn try_from(n: BigUint) -> Result<$t, BigNumError> {
+                <$t>::try_from(&n)
+            }
+        }
+
+        impl TryFrom<&BigInt> for $t {
+            type Error = BigNumError;
+
+            fn try_from(n: &BigInt) -> Result<$t, BigNumError> {
+                let n = i128::try_from(n)?;
+                <$t>::try_from(n).map_err(|_| {
+                    if n < 0 && <$t>::MIN == 0 { BigNumError::Negative } else { BigNumError::OutOfRange }
+                })
+            }
+        }
+
+        impl TryFrom<BigInt> for $t {
+            type Error = BigNumError;
+
+            fn try_from(n: BigInt) -> Result<$t, BigNumError> {
+                <$t>::try_from(&n)
+            }
+        }
+    )*}
+}
+
+from_unsigned!(u8, u16, u32, u64, usize);
+from_signed!(i8, i16, i32, i64, isize);
+try_into_primitive!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+// x * 2^e for x >= 1
+fn scale(x: f64, e: usize) -> f64 {
+    if e > 1023 {
+        return f64::INFINITY;
+    }
+    x * f64::from_bits((1023 + e as u64) << 52)
+}
+
+impl BigUint {
+    // the nearest f64, ties to even; numbers past f64::MAX become infinity
+    pub fn to_f64(&self) -> f64 {
+        let bits = self.bits();
+        if bits <= 64 {
+            return self.limbs()[0] as f64;
+        }
+        // the bits below the top 64 only matter as a tie breaker, so
+        // they are folded into the lowest one
+        let shift = bits - 64;
+        let top = (self >> shift).limbs()[0];
+        let sticky = self.trailing_zeros().unwrap_or(0) < shift;
+        scale((top | sticky as u64) as f64, shift)
+    }
+
+    // the integer part of x; None for NaN, the infinities and numbers
+    // that are negative after truncation
+    pub fn from_f64(x: f64) -> Option<BigUint> {
+        if !x.is_finite() || x <= -1.0 {
+            return None;
+        }
+        let x = x.trunc();
+        if x == 0.0 {
+            return Some(BigUint::from_u64(0));
+        }
+        // x >= 1, so it is normal: mantissa * 2^(exponent - 1075)
+        let bits = x.to_bits();
+        let exponent = ((bits >> 52) & 0x7FF) as i64 - 1075;
+        let mantissa = (bits & ((1 << 52) - 1)) | (1 << 52);
+        if exponent >= 0 {
+            Some(BigUint::from_u64(mantissa) << exponent as usize)
+        } else {
+            Some(BigUint::from_u64(mantissa >> -exponent))
+        }
+    }
+
+    // big-endian bytes without leading zeros; zero is [0]
+    pub fn to_bytes_be(&self) -> Vec<u8> {
+        let mut bytes = self.to_bytes_le();
+        bytes.reverse();
+        bytes
+    }
+
+    // little-endian bytes without trailing zeros; zero is [0]
+    pub fn to_bytes_le(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = self.limbs().iter().flat_map(|x| x.to_le_bytes()).collect();
+        while bytes.len() > 1 && bytes[bytes.len() - 1] == 0 {
+            bytes.pop();
+        }
+        bytes
+    }
+
+    pub fn from_bytes_be(bytes: &[u8]) -> BigUint {
+        let le: Vec<u8> = bytes.iter().rev().cloned().collect();
+        BigUint::from_bytes_le(&le)
+    }
+
+    pub fn from_bytes_le(bytes: &[u8]) -> BigUint {
+        BigUint::from_limbs(bytes.chunks(8).map(|chunk| {
+            let mut limb = [0; 8];
+            limb[..chunk.len()].copy_from_slice(chunk);
+            u64::from_le_bytes(limb)
+        }).collect())
+    }
+}
+
+// -x in two's complement, over the width of the bytes
+fn negate_bytes(bytes: &mut [u8]) {
+    let mut carry = true;
+    for b in bytes.iter_mut() {
+        let (v, c) = (!*b).overflowing_add(carry as u8);
+        *b = v;
+        carry = c;
+    }
+}
+
+impl BigInt {
+    pub fn to_f64(&self) -> f64 {
+        let x = self.num.to_f64();
+        if self.is_negative() { -x } else { x }
+    }
+
+    // the integer part of x, rounded towards zero; None for NaN and
+    // the infinities
+    pub fn from_f64(x: f64) -> Option<BigInt> {
+        let num = BigUint::from_f64(x.abs())?;
+        let sign = if x < 0.0 { IntSign::Neg } else { IntSign::Pos };
+        Some(BigInt::from_biguint(sign, num))
+    }
+
+    // the shortest big-endian two's complement form
+    pub fn to_signed_bytes_be(&self) -> Vec<u8> {
+        let mut bytes = self.to_signed_bytes_le();
+        bytes.reverse();
+        bytes
+    }
+
+    // the shortest little-endian two's complement form
+    pub fn to_signed_bytes_le(&self) -> Vec<u8> {
+        let mut bytes = self.num.to_bytes_le();
+        bytes.push(0);
+        let pad = if self.is_negative() {
+            negate_bytes(&mut bytes);
+            0xFF
+        } else {
+            0
+        };
+        // a top byte that only repeats the sign of the one below it
+        // can go
+        while bytes.len() > 1 && bytes[bytes.len() - 1] == pad
+              && (bytes[bytes.len() - 2] & 0x80 == pad & 0x80) {
+            bytes.pop();
+        }
+        bytes
+    }
+
+    pub fn from_signed_bytes_be(bytes: &[u8]) -> BigInt {
+        let le: Vec<u8> = bytes.iter().rev().cloned().collect();
+        BigInt::from_signed_bytes_le(&le)
+    }
+
+    pub fn from_signed_bytes_le(bytes: &[u8]) -> BigInt {
+        match bytes.last() {
+            Some(&top) if top & 0x80 != 0 => {
+                let mut magnitude = bytes.to_vec();
+                negate_bytes(&mut magnitude);
+                BigInt::from_biguint(IntSign::Neg, BigUint::from_bytes_le(&magnitude))
+            }
+            _ => BigInt::from_biguint(IntSign::Pos, BigUint::from_bytes_le(bytes)),
+        }
+    }
+}
@@ -0,0 +1,294 @@
+// Constant-time arithmetic for secret operands. CtUint is a fixed-width
+// integer whose operations branch and index memory only on the width,
+// never on the value, and Choice is a secret boolean kept as a mask.
+// Widths, moduli and limb positions are public; everything else is not.
+//
+// The values go through black_box where a mask is made, so the optimizer
+// cannot see that it only ever holds 0 or !0 and turn selects back into
+// branches.
+
+use std::hint::black_box;
+
+use super::BigUint;
+
+#[derive(Clone,Copy,Debug)]
+pub struct Choice(u64);
+
+impl Choice {
+    pub fn from_bool(b: bool) -> Choice {
+        Choice(black_box((b as u64).wrapping_neg()))
+    }
+
+    // the mask is all ones when x is 1 and zero when x is 0
+    fn from_bit(x: u64) -> Choice {
+        Choice(black_box(x & 1).wrapping_neg())
+    }
+
+    fn nonzero(x: u64) -> Choice {
+        Choice::from_bit((x | x.wrapping_neg()) >> 63)
+    }
+
+    pub(crate) fn mask(self) -> u64 {
+        self.0
+    }
+
+    pub(crate) fn from_mask(mask: u64) -> Choice {
+        Choice(mask)
+    }
+
+    // reveals the secret; only for results that are allowed to be public
+    pub fn to_bool(self) -> bool {
+        black_box(self.0) != 0
+    }
+}
+
+// mask ? b : a
+fn select_limb(a: u64, b: u64, choice: Choice) -> u64 {
+    a ^ (choice.0 & (a ^ b))
+}
+
+// a + b + carry, returning the carry out in the high word
+fn adc(a: u64, b: u64, carry: u64) -> (u64, u64) {
+    let s = a as u128 + b as u128 + carry as u128;
+    (s as u64, (s >> 64) as u64)
+}
+
+// a - b - borrow, returning the borrow out as 0 or 1
+fn sbb(a: u64, b: u64, borrow: u64) -> (u64, u64) {
+    let d = (a as u128).wrapping_sub(b as u128).wrapping_sub(borrow as u128);
+    (d as u64, (d >> 127) as u64)
+}
+
+// a + b * c + carry
+fn mac(a: u64, b: u64, c: u64, carry: u64) -> (u64, u64) {
+    let s = a as u128 + b as u128 * c as u128 + carry as u128;
+    (s as u64, (s >> 64) as u64)
+}
+
+// a fixed number of little-endian limbs, all of them significant
+#[derive(Clone,Debug)]
+pub struct CtUint {
+    limbs: Vec<u64>,
+}
+
+impl CtUint {
+    pub fn zero(width: usize) -> CtUint {
+        assert!(width > 0, "CtUint needs at least one limb");
+        CtUint { limbs: vec![0; width] }
+    }
+
+    // panics if n does not fit; this reveals the length of n, but not
+    // its value
+    pub fn from_biguint(n: &BigUint, width: usize) -> CtUint {
+        assert!(width > 0, "CtUint needs at least one limb");
+        assert!(n.limbs().len() <= width, "the number does not fit in the target type");
+        let mut limbs = n.limbs().to_vec();
+        limbs.resize(width, 0);
+        CtUint { limbs }
+    }
+
+    pub fn to_biguint(&self) -> BigUint {
+        BigUint::from_limbs(self.limbs.clone())
+    }
+
+    // in limbs
+    pub fn width(&self) -> usize {
+        self.limbs.len()
+    }
+
+    pub fn limbs(&self) -> &[u64] {
+        &self.limbs
+    }
+
+    fn check_width(&self, other: &CtUint) {
+        assert_eq!(self.width(), other.width(), "CtUint operands need the same width");
+    }
+
+    // the sum modulo 2^(64 * width), and whether it wrapped
+    pub fn overflowing_add(&self, other: &CtUint) -> (CtUint, Choice) {
+        self.check_width(other);
+        let mut carry = 0;
+        let limbs = self.limbs.iter().zip(&other.limbs).map(|(&a, &b)| {
+            let (s, c) = adc(a, b, carry);
+            carry = c;
+            s
+        }).collect();
+        (CtUint { limbs }, Choice::from_bit(carry))
+    }
+
+    // the difference modulo 2^(64 * width), and whether it wrapped
+    pub fn overflowing_sub(&self, other: &CtUint) -> (CtUint, Choice) {
+        self.check_width(other);
+        let mut borrow = 0;
+        let limbs = self.limbs.iter().zip(&other.limbs).map(|(&a, &b)| {
+            let (d, br) = sbb(a, b, borrow);
+            borrow = br;
+            d
+        }).collect();
+        (CtUint { limbs }, Choice::from_bit(borrow))
+    }
+
+    pub fn ct_eq(&self, other: &CtUint) -> Choice {
+        self.check_width(other);
+        let diff = self.limbs.iter().zip(&other.limbs).fold(0, |acc, (&a, &b)| acc | (a ^ b));
+        !Choice::nonzero(diff)
+    }
+
+    pub fn ct_lt(&self, other: &CtUint) -> Choice {
+        self.overflowing_sub(other).1
+    }
+
+    pub fn ct_gt(&self, other: &CtUint) -> Choice {
+        other.ct_lt(self)
+    }
+
+    pub fn ct_is_zero(&self) -> Choice {
+        !Choice::nonzero(self.limbs.iter().fold(0, |acc, &x| acc | x))
+    }
+
+    // bit i, for a public i
+    pub fn bit(&self, i: usize) -> Choice {
+        Choice::from_bit(self.limbs[i / 64] >> (i % 64))
+    }
+
+    // choice ? b : a
+    pub fn select(a: &CtUint, b: &CtUint, choice: Choice) -> CtUint {
+        a.check_width(b);
+        let limbs = a.limbs.iter().zip(&b.limbs).map(|(&x, &y)| select_limb(x, y, choice)).collect();
+        CtUint { limbs }
+    }
+
+    // self = choice ? other : self
+    pub fn conditional_assign(&mut self, other: &CtUint, choice: Choice) {
+        self.check_width(other);
+        for (x, &y) in self.limbs.iter_mut().zip(&other.limbs) {
+            *x = select_limb(*x, y, choice);
+        }
+    }
+
+    // swaps a and b if choice is set
+    pub fn conditional_swap(a: &mut CtUint, b: &mut CtUint, choice: Choice) {
+        a.check_width(b);
+        for (x, y) in a.limbs.iter_mut().zip(b.limbs.iter_mut()) {
+            let t = choice.0 & (*x ^ *y);
+            *x ^= t;
+            *y ^= t;
+        }
+    }
+}
+
+// Montgomery multiplication modulo a public odd modulus, with the final
+// subtraction done by a select instead of a branch
+pub struct CtMontgomery {
+    modulus: CtUint,
+    // -modulus^-1 mod 2^64
+    m_inv: u64,
+    // R mod modulus and R^2 mod modulus, R = 2^(64 * width)
+    one: CtUint,
+    r2: CtUint,
+}
+
+impl CtMontgomery {
+    // the modulus is public, so the setup uses the ordinary arithmetic
+    pub fn new(modulus: &BigUint) -> CtMontgomery {
+        assert!(modulus.test_bit(0), "Montgomery arithmetic needs an odd modulus");
+        let width = modulus.limbs().len();
+        let m0 = modulus.limbs()[0];
+        let mut inv: u64 = 1;
+        for _ in 0..6 {
+            inv = inv.wrapping_mul(2u64.wrapping_sub(m0.wrapping_mul(inv)));
+        }
+        let one = &(BigUint::from_u64(1) << (64 * width)) % modulus;
+        let r2 = &one.square() % modulus;
+        CtMontgomery {
+            modulus: CtUint::from_biguint(modulus, width),
+            m_inv: inv.wrapping_neg(),
+            one: CtUint::from_biguint(&one, width),
+            r2: CtUint::from_biguint(&r2, width),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.modulus.width()
+    }
+
+    pub fn modulus(&self) -> BigUint {
+        self.modulus.to_biguint()
+    }
+
+    // a * b * R^-1 mod modulus, for a * b < modulus * R
+    pub fn mul(&self, a: &CtUint, b: &CtUint) -> CtUint {
+        a.check_width(&self.modulus);
+        b.check_width(&self.modulus);
+        let (m, n) = (&self.modulus.limbs, self.width());
+        // CIOS: interleave the multiplication and the reduction so t
+        // stays at n + 2 limbs
+        let mut t = vec![0; n + 2];
+        for &ai in &a.limbs {
+            let mut carry = 0;
+            for (tj, &bj) in t.iter_mut().zip(&b.limbs) {
+                let (s, c) = mac(*tj, ai, bj, carry);
+                *tj = s;
+                carry = c;
+            }
+            let (s, c) = adc(t[n], carry, 0);
+            t[n] = s;
+            t[n + 1] = c;
+
+            let u = t[0].wrapping_mul(self.m_inv);
+            let (_, mut carry) = mac(t[0], u, m[0], 0);
+            for j in 1..n {
+                let (s, c) = mac(t[j], u, m[j], carry);
+                t[j - 1] = s;
+                carry = c;
+            }
+            let (s, c) = adc(t[n], carry, 0);
+            t[n - 1] = s;
+            t[n] = t[n + 1] + c;
+        }
+
+        // t < 2 * modulus; subtract once unless that goes negative
+        let mut borrow = 0;
+        let reduced: Vec<u64> = t[..n].iter().zip(m).map(|(&x, &y)| {
+            let (d, br) = sbb(x, y, borrow);
+            borrow = br;
+            d
+        }).collect();
+        let (_, borrow) = sbb(t[n], 0, borrow);
+        let keep = Choice::from_bit(borrow);
+        let limbs = reduced.iter().zip(&t[..n]).map(|(&r, &x)| select_limb(r, x, keep)).collect();
+        CtUint { limbs }
+    }
+
+    // x * R mod modulus, for any x of the right width
+    pub fn to_montgomery(&self, x: &CtUint) -> CtUint {
+        self.mul(x, &self.r2)
+    }
+
+    // x * R^-1 mod modulus
+    pub fn from_montgomery(&self, x: &CtUint) -> CtUint {
+        let mut one = CtUint::zero(self.width());
+        one.limbs[0] = 1;
+        self.mul(x, &one)
+    }
+
+    // a^exp, with a and the result in Montgomery form. The ladder does a
+    // multiplication and a squaring for every bit of exp's full width,
+    // whatever the bits are
+    pub fn pow(&self, a: &CtUint, exp: &CtUint) -> CtUint {
+        let (mut r0, mut r1) = (self.one.clone(), a.clone());
+        for i in (0..64 * exp.width()).rev() {
+            let bit = exp.bit(i);
+            CtUint::conditional_swap(&mut r0, &mut r1, bit);
+            r1 = self.mul(&r0, &r1);
+            r0 = self.mul(&r0, &r0);
+            CtUint::conditional_swap(&mut r0, &mut r1, bit);
+        }
+        r0
+    }
+
+    // base^exp mod modulus, with base and the result in the normal form
+    pub fn mod_pow(&self, base: &CtUint, exp: &CtUint) -> CtUint {
+        self.from_montgomery(&self.pow(&self.to_montgomery(base), exp))
+    }
+}
@@ -0,0 +1,219 @@
+// Division: a short division by a single limb and Knuth's Algorithm D
+// (TAOCP vol. 2, 4.3.1) for multi-limb divisors, plus Newton reciprocals
+// and Barrett reduction for dividing many times by the same number.
+
+use std::cmp::Ordering::{Greater,Less};
+
+use limbs;
+use mul::{mul_into,mul_limbs};
+
+// below this many limbs the reciprocal is computed by plain long division
+pub const RECIPROCAL_THRESHOLD: usize = 32;
+
+// a /= d in place; returns the remainder
+pub fn div_rem_limb(a: &mut [u64], d: u64) -> u64 {
+    let mut rem: u64 = 0;
+    for x in a.iter_mut().rev() {
+        let cur = ((rem as u128) << 64) | (*x as u128);
+        *x = (cur / d as u128) as u64;
+        rem = (cur % d as u128) as u64;
+    }
+    rem
+}
+
+// returns (quotient, remainder); v has to have at least two significant
+// limbs, and u has to be at least as long as v
+pub fn div_rem_knuth(u: &[u64], v: &[u64]) -> (Vec<u64>, Vec<u64>) {
+    let (mut q, mut r, mut vn) = (Vec::new(), Vec::new(), Vec::new());
+    div_rem_knuth_into(u, v, &mut q, &mut r, &mut vn);
+    (q, r)
+}
+
+// the same, writing into buffers that keep their capacity between calls;
+// vn ends up holding the normalized divisor
+pub fn div_rem_knuth_into(u: &[u64], v: &[u64], q: &mut Vec<u64>,
+                          un: &mut Vec<u64>, vn: &mut Vec<u64>) {
+    let n = limbs::trimmed_len(v);
+    q.clear();
+    q.resize(u.len() - n + 1, 0);
+    un.clear();
+    un.resize(u.len() + 1, 0);
+    vn.clear();
+    vn.resize(n, 0);
+    div_rem_knuth_slices(u, v, q, un, vn);
+    un.truncate(n);
+}
+
+// the kernel on caller-provided buffers, with n the significant limbs of
+// v: the quotient goes into q[..u.len() - n + 1] and the remainder into
+// un[..n], which needs room for u.len() + 1 limbs; vn[..n] ends up
+// holding the normalized divisor
+pub fn div_rem_knuth_slices(u: &[u64], v: &[u64], q: &mut [u64],
+                            un: &mut [u64], vn: &mut [u64]) {
+    let n = limbs::trimmed_len(v);
+    let v = &v[..n];
+    debug_assert!(n >= 2 && u.len() >= n);
+    let m = u.len() - n;
+    let (q, un, vn) = (&mut q[..m + 1], &mut un[..u.len() + 1], &mut vn[..n]);
+
+    // normalize, so that the top bit of the divisor is set
+    let shift = v[n - 1].leading_zeros();
+    vn.copy_from_slice(v);
+    shl_bits(vn, shift);
+    un[..u.len()].copy_from_slice(u);
+    un[u.len()] = 0;
+    shl_bits(un, shift);
+
+    let top = vn[n - 1] as u128;
+    let next = vn[n - 2] as u128;
+    let base = 1u128 << 64;
+    for j in (0..m + 1).rev() {
+        // estimate the quotient limb from the top two limbs, then refine
+        // it with the next one, which makes it at most one too big
+        let num = ((un[j + n] as u128) << 64) | (un[j + n - 1] as u128);
+        let mut qhat = num / top;
+        let mut rhat = num % top;
+        while qhat >= base || qhat * next > ((rhat << 64) | un[j + n - 2] as u128) {
+            qhat -= 1;
+            rhat += top;
+            if rhat >= base {
+                break;
+            }
+        }
+
+        let mut qhat = qhat as u64;
+        let borrow = limbs::submul_limb(&mut un[j..j + n], vn, qhat);
+        let (t, under) = un[j + n].overflowing_sub(borrow);
+        un[j + n] = t;
+        if under {
+            qhat -= 1;
+            let carry = limbs::add_assign(&mut un[j..j + n], vn);
+            un[j + n] = un[j + n].wrapping_add(carry);
+        }
+        q[j] = qhat;
+    }
+
+    shr_bits(&mut un[..n], shift);
+}
+
+fn shl_bits(a: &mut [u64], shift: u32) {
+    if shift == 0 {
+        return;
+    }
+    for i in (1..a.len()).rev() {
+        a[i] = (a[i] << shift) | (a[i - 1] >> (64 - shift));
+    }
+    a[0] <<= shift;
+}
+
+fn shr_bits(a: &mut [u64], shift: u32) {
+    if shift == 0 {
+        return;
+    }
+    let len = a.len();
+    for i in 0..len - 1 {
+        a[i] = (a[i] >> shift) | (a[i + 1] << (64 - shift));
+    }
+    a[len - 1] >>= shift;
+}
+
+// floor(B^2n / d), where n is the number of significant limbs in d
+pub fn reciprocal(d: &[u64]) -> Vec<u64> {
+    let n = limbs::trimmed_len(d);
+    let d = &d[..n];
+    let mut pow = vec![0; 2 * n + 1];
+    pow[2 * n] = 1;
+    if n == 1 {
+        div_rem_limb(&mut pow, d[0]);
+        return pow;
+    }
+    if n <= RECIPROCAL_THRESHOLD {
+        return div_rem_knuth(&pow, d).0;
+    }
+
+    // start from the reciprocal of the top limbs, shifted into place,
+    // which is correct to about h limbs; one Newton step doubles that
+    let h = n / 2 + 2;
+    let mut x = vec![0; n - h];
+    x.extend(reciprocal(&d[n - h..]));
+
+    // x += x * (B^2n - d*x) / B^2n
+    let (neg, e) = signed_sub(&pow, &mul_limbs(d, &x));
+    let t = mul_limbs(&x, &e);
+    let t = if t.len() > 2 * n { &t[2 * n..] } else { &[][..] };
+    if neg {
+        limbs::sub_assign(&mut x, &t[..limbs::trimmed_len(t)]);
+    } else {
+        x.push(0);
+        limbs::add_assign(&mut x, &t[..limbs::trimmed_len(t)]);
+    }
+
+    // the estimate is off by a few units at most, fix it up so that
+    // 0 <= B^2n - d*x < d
+    let (mut neg, mut rem) = signed_sub(&pow, &mul_limbs(d, &x));
+    while neg {
+        limbs::sub_assign(&mut x, &[1]);
+        if limbs::cmp(&rem, d) != Greater {
+            rem = limbs::sub_vec(d, &rem);
+            neg = false;
+        } else {
+            limbs::sub_assign(&mut rem, d);
+        }
+    }
+    while limbs::cmp(&rem, d) != Less {
+        limbs::sub_assign(&mut rem, d);
+        x.push(0);
+        limbs::add_assign(&mut x, &[1]);
+    }
+    x.truncate(limbs::trimmed_len(&x));
+    x
+}
+
+// Barrett division (HAC 14.42) of x < B^2n by d with n significant limbs,
+// where mu = reciprocal(d); returns (quotient, remainder)
+pub fn div_rem_barrett(x: &[u64], d: &[u64], mu: &[u64]) -> (Vec<u64>, Vec<u64>) {
+    let (mut q, mut r, mut t) = (Vec::new(), Vec::new(), Vec::new());
+    div_rem_barrett_into(x, d, mu, &mut q, &mut r, &mut t);
+    (q, r)
+}
+
+// the same, writing into buffers that keep their capacity between calls;
+// t holds the products along the way
+pub fn div_rem_barrett_into(x: &[u64], d: &[u64], mu: &[u64], q: &mut Vec<u64>,
+                            r: &mut Vec<u64>, t: &mut Vec<u64>) {
+    let n = limbs::trimmed_len(d);
+    let d = &d[..n];
+    let x = &x[..limbs::trimmed_len(x)];
+    q.clear();
+    r.clear();
+    if x.len() < n {
+        q.push(0);
+        r.extend_from_slice(x);
+        return;
+    }
+
+    let high = &x[n - 1..];
+    t.clear();
+    t.resize(high.len() + mu.len(), 0);
+    mul_into(t, high, mu);
+    if t.len() > n + 1 { q.extend_from_slice(&t[n + 1..]) } else { q.push(0) }
+    t.clear();
+    t.resize(q.len() + n, 0);
+    mul_into(t, q, d);
+    r.extend_from_slice(x);
+    limbs::sub_assign(r, &t[..limbs::trimmed_len(t)]);
+    while limbs::cmp(r, d) != Less {
+        limbs::sub_assign(r, d);
+        q.push(0);
+        limbs::add_assign(q, &[1]);
+    }
+}
+
+// returns (a < b, |a - b|)
+fn signed_sub(a: &[u64], b: &[u64]) -> (bool, Vec<u64>) {
+    if limbs::cmp(a, b) == Less {
+        (true, limbs::sub_vec(b, a))
+    } else {
+        (false, limbs::sub_vec(a, b))
+    }
+}
@@ -0,0 +1,98 @@
+// The native binary encoding, shared by BigUint and BigInt:
+//
+//     version: u8 = 1
+//     sign:    u8, 0 for zero and positive numbers, 1 for negative ones
+//     length:  u64 little-endian, the number of limbs
+//     limbs:   u64 little-endian each, least significant first
+//
+// Only the canonical form is accepted on input: at least one limb, no
+// leading zero limbs and no negative zero. A non-negative BigInt and a
+// BigUint of the same value encode to the same bytes.
+
+use std::io::{self,Read,Write};
+
+use super::{BigUint,BigInt,IntSign};
+
+const VERSION: u8 = 1;
+
+// limbs are read in chunks of this many, so a corrupt length cannot
+// make us allocate far more than the input actually holds
+const READ_CHUNK: usize = 4096;
+
+fn invalid(msg: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+fn write_parts<W: Write>(w: &mut W, negative: bool, n: &BigUint) -> io::Result<()> {
+    w.write_all(&[VERSION, negative as u8])?;
+    w.write_all(&(n.limbs().len() as u64).to_le_bytes())?;
+    let bytes: Vec<u8> = n.limbs().iter().flat_map(|x| x.to_le_bytes()).collect();
+    w.write_all(&bytes)
+}
+
+fn read_parts<R: Read>(r: &mut R) -> io::Result<(bool, BigUint)> {
+    let mut header = [0; 10];
+    r.read_exact(&mut header)?;
+    if header[0] != VERSION {
+        return Err(invalid("Unsupported bignum encoding version"));
+    }
+    let negative = match header[1] {
+        0 => false,
+        1 => true,
+        _ => return Err(invalid("Invalid sign in a bignum encoding")),
+    };
+    let mut len = [0; 8];
+    len.copy_from_slice(&header[2..]);
+    let len = u64::from_le_bytes(len);
+    if len == 0 {
+        return Err(invalid("A bignum encoding needs at least one limb"));
+    }
+
+    let mut limbs = Vec::new();
+    let mut buf = vec![0; 8 * READ_CHUNK];
+    let mut left = len;
+    while left > 0 {
+        let count = left.min(READ_CHUNK as u64) as usize;
+        r.read_exact(&mut buf[..8 * count])?;
+        limbs.extend(buf[..8 * count].chunks(8).map(|c| {
+            let mut limb = [0; 8];
+            limb.copy_from_slice(c);
+            u64::from_le_bytes(limb)
+        }));
+        left -= count as u64;
+    }
+
+    if limbs.len() > 1 && limbs[limbs.len() - 1] == 0 {
+        return Err(invalid("Leading zero limbs in a bignum encoding"));
+    }
+    let n = BigUint::from_limbs(limbs);
+    if negative && n.is_zero() {
+        return Err(invalid("Negative zero in a bignum encoding"));
+    }
+    Ok((negative, n))
+}
+
+impl BigUint {
+    pub fn write_to<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        write_parts(w, false, self)
+    }
+
+    pub fn read_from<R: Read>(r: &mut R) -> io::Result<BigUint> {
+        match read_parts(r)? {
+            (false, n) => Ok(n),
+            (true, _) => Err(invalid("A negative number cannot be read as a BigUint")),
+        }
+    }
+}
+
+impl BigInt {
+    pub fn write_to<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        write_parts(w, self.is_negative(), &self.num)
+    }
+
+    pub fn read_from<R: Read>(r: &mut R) -> io::Result<BigInt> {
+        let (negative, n) = read_parts(r)?;
+        let sign = if negative { IntSign::Neg } else { IntSign::Pos };
+        Ok(BigInt::from_biguint(sign, n))
+    }
+}
@@ -0,0 +1,47 @@
+// The error type of the fallible conversions, parsers and checked
+// arithmetic.
+
+use std::error::Error;
+use std::fmt;
+
+#[derive(Clone,Copy,Debug,PartialEq,Eq)]
+pub enum BigNumError {
+    // a base outside [min, max], the range the function supports
+    InvalidRadix { radix: u64, min: u64, max: u64 },
+    // nothing to parse
+    EmptyString,
+    // a character that is not a digit in the base
+    InvalidDigit,
+    // a float literal with a malformed exponent
+    InvalidExponent,
+    DivisionByZero,
+    // a negative value where only unsigned ones fit
+    Negative,
+    // a value too big for the target type
+    OutOfRange,
+}
+
+impl fmt::Display for BigNumError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            BigNumError::InvalidRadix { radix, min, max } =>
+                write!(f, "base {} is outside the supported range [{}, {}]", radix, min, max),
+            BigNumError::EmptyString => f.write_str("cannot parse a number from an empty string"),
+            BigNumError::InvalidDigit => f.write_str("invalid digit for the given base"),
+            BigNumError::InvalidExponent => f.write_str("invalid exponent"),
+            BigNumError::DivisionByZero => f.write_str("division by zero"),
+            BigNumError::Negative => f.write_str("negative value in an unsigned context"),
+            BigNumError::OutOfRange => f.write_str("the number does not fit in the target type"),
+        }
+    }
+}
+
+impl Error for BigNumError {}
+
+// Err(InvalidRadix) unless min <= radix <= max
+pub fn check_radix(radix: u64, min: u64, max: u64) -> Result<(), BigNumError> {
+    if !(min..=max).contains(&radix) {
+        return Err(BigNumError::InvalidRadix { radix, min, max });
+    }
+    Ok(())
+}
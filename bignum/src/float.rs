@@ -0,0 +1,393 @@
+// Binary floating point with a per-value precision. A BigFloat is
+// mantissa * 2^exp, where the mantissa has at most `prec` bits and is
+// odd unless the value is zero, so every number has one representation.
+// There are no infinities, NaNs or signed zeros, and the exponent range
+// is only limited by i64.
+
+use std::cmp::{max,min,Ordering};
+use std::convert::TryFrom;
+
+use super::{BigUint,BigInt,BigRational,IntSign,BigNumError};
+
+#[derive(Clone,Copy,PartialEq,Eq,Debug)]
+pub enum RoundingMode {
+    // the IEEE 754 default, ties to the even neighbour
+    NearestEven,
+    // ties away from zero
+    NearestAway,
+    TowardZero,
+    TowardPositive,
+    TowardNegative,
+}
+
+#[derive(Clone)]
+pub struct BigFloat {
+    mantissa: BigInt,
+    exp: i64,
+    prec: usize,
+}
+
+fn sign(negative: bool) -> IntSign {
+    if negative { IntSign::Neg } else { IntSign::Pos }
+}
+
+// sign * mag * 2^exp rounded to a multiple of 2^lowest
+fn round_at(negative: bool, mag: BigUint, exp: i64, lowest: i64, prec: usize,
+            mode: RoundingMode) -> BigFloat {
+    if mag.is_zero() {
+        return BigFloat::zero(prec);
+    }
+    if exp >= lowest {
+        return BigFloat::normalized(BigInt::from_biguint(sign(negative), mag), exp, prec);
+    }
+
+    let shift = (lowest - exp) as usize;
+    let kept = &mag >> shift;
+    let half = mag.test_bit(shift - 1);
+    let sticky = mag.trailing_zeros().unwrap_or(0) < shift - 1;
+    let up = match mode {
+        RoundingMode::NearestEven => half && (sticky || kept.test_bit(0)),
+        RoundingMode::NearestAway => half,
+        RoundingMode::TowardZero => false,
+        RoundingMode::TowardPositive => (half || sticky) && !negative,
+        RoundingMode::TowardNegative => (half || sticky) && negative,
+    };
+    let kept = if up { kept + 1 } else { kept };
+    BigFloat::normalized(BigInt::from_biguint(sign(negative), kept), lowest, prec)
+}
+
+// sign * mag * 2^exp rounded to prec significant bits
+fn round(negative: bool, mag: BigUint, exp: i64, prec: usize, mode: RoundingMode) -> BigFloat {
+    let top = exp + mag.bits() as i64;
+    round_at(negative, mag, exp, top - prec as i64, prec, mode)
+}
+
+// the largest decimal exponent parse accepts; wider values are allowed
+// proportionally bigger powers of ten
+fn max_parse_exponent(prec: usize) -> u64 {
+    max(100_000, 4 * prec as u64)
+}
+
+// x * 2^e for integers x < 2^54 whose product is representable
+fn scale_f64(x: f64, e: i64) -> f64 {
+    let pow2 = |e: i64| f64::from_bits(((1023 + e) as u64) << 52);
+    if e < -1000 {
+        x * pow2(e + 200) * pow2(-200)
+    } else {
+        x * pow2(e)
+    }
+}
+
+impl BigFloat {
+    // strips the trailing zero bits off an exact value
+    fn normalized(mantissa: BigInt, exp: i64, prec: usize) -> BigFloat {
+        match mantissa.num.trailing_zeros() {
+            None => BigFloat::zero(prec),
+            Some(tz) => BigFloat {
+                mantissa: BigInt::from_biguint(mantissa.sign, &mantissa.num >> tz),
+                exp: exp + tz as i64,
+                prec,
+            },
+        }
+    }
+
+    pub fn zero(prec: usize) -> BigFloat {
+        assert!(prec > 0, "BigFloat needs at least one bit of precision");
+        BigFloat { mantissa: BigInt::zero(), exp: 0, prec }
+    }
+
+    pub fn from_bigint(n: &BigInt, prec: usize, mode: RoundingMode) -> BigFloat {
+        assert!(prec > 0, "BigFloat needs at least one bit of precision");
+        round(n.is_negative(), n.num.clone(), 0, prec, mode)
+    }
+
+    pub fn from_i64(n: i64, prec: usize) -> BigFloat {
+        BigFloat::from_bigint(&BigInt::from_i64(n), prec, RoundingMode::NearestEven)
+    }
+
+    // None for NaN and the infinities
+    pub fn from_f64(x: f64, prec: usize, mode: RoundingMode) -> Option<BigFloat> {
+        assert!(prec > 0, "BigFloat needs at least one bit of precision");
+        if !x.is_finite() {
+            return None;
+        }
+        let bits = x.to_bits();
+        let biased = ((bits >> 52) & 0x7FF) as i64;
+        let fraction = bits & ((1 << 52) - 1);
+        let (mantissa, exp) = if biased == 0 { (fraction, -1074) }
+                              else { (fraction | (1 << 52), biased - 1075) };
+        Some(round(x < 0.0, BigUint::from_u64(mantissa), exp, prec, mode))
+    }
+
+    // the nearest f64, ties to even
+    pub fn to_f64(&self) -> f64 {
+        if self.is_zero() {
+            return 0.0;
+        }
+        let top = self.top();
+        if top > 1024 {
+            return if self.is_negative() { f64::NEG_INFINITY } else { f64::INFINITY };
+        }
+        // 53 bits, or fewer where the result is subnormal
+        let lowest = max(top - 53, -1074);
+        let r = round_at(self.is_negative(), self.mantissa.num.clone(), self.exp, lowest,
+                         self.prec, RoundingMode::NearestEven);
+        let x = scale_f64(r.mantissa.num.limbs()[0] as f64, r.exp);
+        if r.is_negative() { -x } else { x }
+    }
+
+    // the exact value
+    pub fn to_rational(&self) -> BigRational {
+        let pow2 = BigInt::from_biguint(IntSign::Pos, BigUint::from_u64(1) << self.exp.unsigned_abs() as usize);
+        if self.exp >= 0 {
+            BigRational::from_integer(&self.mantissa * &pow2)
+        } else {
+            BigRational::new(self.mantissa.clone(), pow2)
+        }
+    }
+
+    pub fn mantissa(&self) -> &BigInt {
+        &self.mantissa
+    }
+
+    pub fn exponent(&self) -> i64 {
+        self.exp
+    }
+
+    pub fn precision(&self) -> usize {
+        self.prec
+    }
+
+    pub fn with_precision(&self, prec: usize, mode: RoundingMode) -> BigFloat {
+        assert!(prec > 0, "BigFloat needs at least one bit of precision");
+        round(self.is_negative(), self.mantissa.num.clone(), self.exp, prec, mode)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.mantissa.is_zero()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.mantissa.is_negative()
+    }
+
+    pub fn abs(&self) -> BigFloat {
+        BigFloat { mantissa: self.mantissa.abs(), exp: self.exp, prec: self.prec }
+    }
+
+    pub fn neg(&self) -> BigFloat {
+        BigFloat { mantissa: -&self.mantissa, exp: self.exp, prec: self.prec }
+    }
+
+    // |self| < 2^top; only meaningful for non-zero values
+    fn top(&self) -> i64 {
+        self.exp + self.mantissa.num.bits() as i64
+    }
+
+    pub fn add_round(&self, other: &BigFloat, prec: usize, mode: RoundingMode) -> BigFloat {
+        if self.is_zero() {
+            return other.with_precision(prec, mode);
+        }
+        if other.is_zero() {
+            return self.with_precision(prec, mode);
+        }
+        let (big, small) = if self.top() >= other.top() { (self, other) } else { (other, self) };
+
+        // a summand entirely below both the last bit of the other one and
+        // the rounding position only decides the direction of rounding,
+        // so a single bit just below that point does the same job and
+        // keeps the shifts short
+        let floor = min(big.exp, big.top() - prec as i64) - 2;
+        let (small_mantissa, small_exp) = if small.top() <= floor {
+            (BigInt::from_biguint(small.mantissa.sign, BigUint::from_u64(1)), floor - 1)
+        } else {
+            (small.mantissa.clone(), small.exp)
+        };
+
+        let exp = min(big.exp, small_exp);
+        let sum = &(&big.mantissa << (big.exp - exp) as usize)
+                + &(&small_mantissa << (small_exp - exp) as usize);
+        round(sum.is_negative(), sum.num, exp, prec, mode)
+    }
+
+    pub fn sub_round(&self, other: &BigFloat, prec: usize, mode: RoundingMode) -> BigFloat {
+        self.add_round(&other.neg(), prec, mode)
+    }
+
+    pub fn mul_round(&self, other: &BigFloat, prec: usize, mode: RoundingMode) -> BigFloat {
+        let product = &self.mantissa * &other.mantissa;
+        round(product.is_negative(), product.num, self.exp + other.exp, prec, mode)
+    }
+
+    // panics if other is zero
+    pub fn div_round(&self, other: &BigFloat, prec: usize, mode: RoundingMode) -> BigFloat {
+        if other.is_zero() {
+            panic!("BigFloat division by zero");
+        }
+        if self.is_zero() {
+            return BigFloat::zero(prec);
+        }
+        // enough quotient bits for the rounding position and a guard bit,
+        // with the remainder folded into one more sticky bit below them
+        let (a, b) = (&self.mantissa.num, &other.mantissa.num);
+        let shift = max(0, prec as i64 + 2 + b.bits() as i64 - a.bits() as i64) as usize;
+        let (quot, rem) = (a << shift).divmod(b);
+        let mut quot = quot << 1;
+        if !rem.is_zero() {
+            quot.set_bit(0);
+        }
+        let negative = self.is_negative() != other.is_negative();
+        round(negative, quot, self.exp - other.exp - shift as i64 - 1, prec, mode)
+    }
+
+    // panics for negative numbers
+    pub fn sqrt_round(&self, prec: usize, mode: RoundingMode) -> BigFloat {
+        if self.is_negative() {
+            panic!("square root of a negative BigFloat");
+        }
+        if self.is_zero() {
+            return BigFloat::zero(prec);
+        }
+        // make the exponent even, then scale until the root has enough bits
+        let (mut m, mut exp) = (self.mantissa.num.clone(), self.exp);
+        if exp % 2 != 0 {
+            m <<= 1;
+            exp -= 1;
+        }
+        let half_bits = m.bits() as i64 / 2;
+        let shift = max(0, prec as i64 + 3 - half_bits) as usize;
+        let m = m << (2 * shift);
+        let root = m.isqrt();
+        let exact = root.square() == m;
+        let mut root = root << 1;
+        if !exact {
+            root.set_bit(0);
+        }
+        round(false, root, exp / 2 - shift as i64 - 1, prec, mode)
+    }
+
+    // the operators use the larger of the two precisions and round to
+    // nearest, ties to even
+    pub fn add(&self, other: &BigFloat) -> BigFloat {
+        self.add_round(other, max(self.prec, other.prec), RoundingMode::NearestEven)
+    }
+
+    pub fn sub(&self, other: &BigFloat) -> BigFloat {
+        self.sub_round(other, max(self.prec, other.prec), RoundingMode::NearestEven)
+    }
+
+    pub fn mul(&self, other: &BigFloat) -> BigFloat {
+        self.mul_round(other, max(self.prec, other.prec), RoundingMode::NearestEven)
+    }
+
+    pub fn div(&self, other: &BigFloat) -> BigFloat {
+        self.div_round(other, max(self.prec, other.prec), RoundingMode::NearestEven)
+    }
+
+    pub fn sqrt(&self) -> BigFloat {
+        self.sqrt_round(self.prec, RoundingMode::NearestEven)
+    }
+
+    // compares the absolute values
+    pub fn cmp_abs(&self, other: &BigFloat) -> Ordering {
+        match (self.is_zero(), other.is_zero()) {
+            (true, true) => return Ordering::Equal,
+            (true, false) => return Ordering::Less,
+            (false, true) => return Ordering::Greater,
+            (false, false) => (),
+        }
+        match self.top().cmp(&other.top()) {
+            Ordering::Equal => (),
+            ord => return ord,
+        }
+        let exp = min(self.exp, other.exp);
+        (&self.mantissa.num << (self.exp - exp) as usize)
+            .cmp(&(&other.mantissa.num << (other.exp - exp) as usize))
+    }
+
+    // decimal scientific or positional notation, e.g. "1.5e-7",
+    // "-3.25"; "inf" and "nan" are not accepted. The power of ten is
+    // computed exactly, so exponents past 100000 or 4 * prec, whichever
+    // is larger, are rejected with InvalidExponent
+    pub fn parse(s: &str, prec: usize, mode: RoundingMode) -> Result<BigFloat, BigNumError> {
+        assert!(prec > 0, "BigFloat needs at least one bit of precision");
+        let (negative, s) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        let (s, exp10) = match s.find(['e', 'E']) {
+            Some(pos) => {
+                let exp = s[pos + 1..].parse::<i64>()
+                    .map_err(|_| BigNumError::InvalidExponent)?;
+                if exp.unsigned_abs() > max_parse_exponent(prec) {
+                    return Err(BigNumError::InvalidExponent);
+                }
+                (&s[..pos], exp)
+            }
+            None => (s, 0),
+        };
+        let (int, frac) = s.split_once('.').unwrap_or((s, ""));
+        let digits = format!("{}{}", int, frac);
+        if digits.is_empty() {
+            return Err(BigNumError::EmptyString);
+        }
+        if !digits.bytes().all(|c| c.is_ascii_digit()) {
+            return Err(BigNumError::InvalidDigit);
+        }
+        let mag = BigUint::from_base_string(&digits, 10)?;
+        let exp10 = exp10 - frac.len() as i64;
+
+        let exp10_abs = u32::try_from(exp10.unsigned_abs())
+            .map_err(|_| BigNumError::InvalidExponent)?;
+        let pow10 = BigUint::from_u64(10).pow(exp10_abs);
+        if exp10 >= 0 {
+            return Ok(round(negative, &mag * &pow10, 0, prec, mode));
+        }
+        // both are exact, so the quotient is rounded once
+        let num = BigFloat::normalized(BigInt::from_biguint(sign(negative), mag), 0, 1);
+        let den = BigFloat::normalized(BigInt::from_biguint(IntSign::Pos, pow10), 0, 1);
+        Ok(num.div_round(&den, prec, mode))
+    }
+
+    // the value rounded to sig significant decimal digits, as the digits
+    // and the power of ten of the first one
+    pub fn to_decimal_digits(&self, sig: usize) -> (String, i64) {
+        assert!(sig > 0, "at least one significant digit is needed");
+        if self.is_zero() {
+            return ("0".repeat(sig), 0);
+        }
+        let ten = BigUint::from_u64(10);
+        let mag = &self.mantissa.num;
+        // the first digit's power of ten, possibly off by one
+        let mut point = ((self.top() - 1) as f64 * std::f64::consts::LOG10_2).floor() as i64;
+        loop {
+            // digits = round(|self| * 10^(sig - 1 - point)), ties to even
+            let k = sig as i64 - 1 - point;
+            let pow10 = ten.pow(k.unsigned_abs() as u32);
+            let (mut num, mut den) = (mag.clone(), BigUint::from_u64(1));
+            if k >= 0 { num = &num * &pow10; } else { den = pow10; }
+            if self.exp >= 0 { num <<= self.exp as usize; } else { den <<= self.exp.unsigned_abs() as usize; }
+            let (quot, rem) = num.divmod(&den);
+            let twice = rem << 1;
+            let quot = match twice.cmp(&den) {
+                Ordering::Greater => quot + 1,
+                Ordering::Equal if quot.test_bit(0) => quot + 1,
+                _ => quot,
+            };
+
+            let digits = quot.to_string();
+            if digits.len() > sig {
+                point += 1;
+            } else if digits.len() < sig {
+                point -= 1;
+            } else {
+                return (digits, point);
+            }
+        }
+    }
+
+    // digits enough to tell any two values of this precision apart
+    pub fn default_digits(&self) -> usize {
+        (self.prec as f64 * std::f64::consts::LOG10_2).ceil() as usize + 1
+    }
+}
@@ -0,0 +1,131 @@
+// std::fmt support. Width, fill, alignment, `+`, `#` and zero padding
+// all come from Formatter::pad_integral.
+
+use std::fmt;
+
+use super::{BigUint,BigInt,BigRational,BigFloat,UInt,ALPHABET};
+
+fn digit_string(n: &BigUint, base: u64, lowercase: bool) -> String {
+    let alpha: &[u8] = ALPHABET.as_ref();
+    let digits = n.digits(base).unwrap();
+    digits.iter().rev().map(|&d| {
+        let c = alpha[d as usize] as char;
+        if lowercase { c.to_ascii_lowercase() } else { c }
+    }).collect()
+}
+
+macro_rules! impl_fmt {
+    ($imp:ident, $base:expr, $lowercase:expr, $prefix:expr) => {
+        impl fmt::$imp for BigUint {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.pad_integral(true, $prefix, &digit_string(self, $base, $lowercase))
+            }
+        }
+
+        impl fmt::$imp for BigInt {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                let digits = digit_string(&self.num, $base, $lowercase);
+                f.pad_integral(!self.is_negative(), $prefix, &digits)
+            }
+        }
+
+        impl<const LIMBS: usize> fmt::$imp for UInt<LIMBS> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                let digits = digit_string(&BigUint::from(self), $base, $lowercase);
+                f.pad_integral(true, $prefix, &digits)
+            }
+        }
+    }
+}
+
+impl_fmt!(Display, 10, false, "");
+impl_fmt!(LowerHex, 16, true, "0x");
+impl_fmt!(UpperHex, 16, false, "0x");
+impl_fmt!(Octal, 8, false, "0o");
+impl_fmt!(Binary, 2, false, "0b");
+
+impl fmt::Debug for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl fmt::Debug for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl<const LIMBS: usize> fmt::Debug for UInt<LIMBS> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+// a/b, or just a for integers; with a precision, the decimal expansion
+// rounded to that many places
+impl fmt::Display for BigRational {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match f.precision() {
+            Some(places) => self.to_decimal_string(places),
+            None if self.is_integer() => self.numer().to_string(),
+            None => format!("{}/{}", self.numer(), self.denom()),
+        };
+        match s.strip_prefix('-') {
+            Some(digits) => f.pad_integral(false, "", digits),
+            None => f.pad_integral(true, "", &s),
+        }
+    }
+}
+
+impl fmt::Debug for BigRational {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+// enough digits to survive a round trip at the value's own precision,
+// trailing zeros trimmed: positional for 1e-5 <= |x| < 1e21, otherwise
+// d.ddde±X. Only the magnitude is printed
+fn float_string(x: &BigFloat) -> String {
+    let (digits, point) = x.to_decimal_digits(x.default_digits());
+    let digits = digits.trim_end_matches('0');
+    if digits.is_empty() {
+        return "0".to_string();
+    }
+    if !(-5..=20).contains(&point) {
+        let (first, rest) = digits.split_at(1);
+        let exp = if point < 0 { format!("-{}", -point) } else { format!("+{}", point) };
+        return if rest.is_empty() { format!("{}e{}", first, exp) }
+               else { format!("{}.{}e{}", first, rest, exp) };
+    }
+    if point < 0 {
+        format!("0.{}{}", "0".repeat((-point - 1) as usize), digits)
+    } else if (point as usize) < digits.len() - 1 {
+        let (int, frac) = digits.split_at(point as usize + 1);
+        format!("{}.{}", int, frac)
+    } else {
+        format!("{}{}", digits, "0".repeat(point as usize + 1 - digits.len()))
+    }
+}
+
+// with a precision, the exact value rounded to that many decimal places
+impl fmt::Display for BigFloat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match f.precision() {
+            Some(places) => self.to_rational().to_decimal_string(places),
+            None if self.is_negative() => format!("-{}", float_string(self)),
+            None => float_string(self),
+        };
+        match s.strip_prefix('-') {
+            Some(digits) => f.pad_integral(false, "", digits),
+            None => f.pad_integral(true, "", &s),
+        }
+    }
+}
+
+impl fmt::Debug for BigFloat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
@@ -0,0 +1,133 @@
+// In-place arithmetic. These write into buffers the caller already owns,
+// so a loop that keeps updating the same numbers stops allocating once
+// the buffers have grown to size.
+
+use std::cmp::Ordering::Less;
+
+use super::{BigUint,long_mul};
+use div;
+use limbs;
+
+// temporary storage for the in-place operations that need some;
+// keep one around and hand it to every call
+#[derive(Default)]
+pub struct Scratch {
+    pub(crate) buf: Vec<u64>,
+    // more buffers for the recursive algorithms, one pair per level
+    spare: Vec<Vec<u64>>,
+}
+
+impl Scratch {
+    pub fn new() -> Scratch {
+        Scratch { buf: Vec::new(), spare: Vec::new() }
+    }
+
+    pub fn with_capacity(limbs: usize) -> Scratch {
+        Scratch { buf: Vec::with_capacity(limbs), spare: Vec::new() }
+    }
+
+    // number of limbs it can hold without allocating
+    pub fn capacity(&self) -> usize {
+        self.buf.capacity()
+    }
+
+    // an empty buffer that keeps the capacity it had when it was given back
+    pub(crate) fn take(&mut self) -> Vec<u64> {
+        self.spare.pop().unwrap_or_default()
+    }
+
+    pub(crate) fn give_back(&mut self, buf: Vec<u64>) {
+        self.spare.push(buf);
+    }
+}
+
+fn set_limbs(dst: &mut Vec<u64>, src: &[u64]) {
+    dst.clear();
+    dst.extend_from_slice(src);
+}
+
+impl BigUint {
+    // number of limbs it can hold without allocating
+    pub fn capacity(&self) -> usize {
+        self.limbs.capacity()
+    }
+
+    pub fn reserve(&mut self, additional: usize) {
+        self.limbs.reserve(additional);
+    }
+
+    // self += other
+    pub fn add_assign(&mut self, other: &BigUint) {
+        let len = limbs::trimmed_len(&other.limbs);
+        if self.limbs.len() < len {
+            self.limbs.resize(len, 0);
+        }
+        let carry = limbs::add_assign(&mut self.limbs, &other.limbs[..len]);
+        if carry > 0 {
+            self.limbs.push(carry);
+        }
+        self.assert_invariants();
+    }
+
+    // self -= other; returns true if other was bigger, in which case
+    // self is left holding other - self
+    pub fn sub_assign(&mut self, other: &BigUint) -> bool {
+        let len = limbs::trimmed_len(&other.limbs);
+        if self.limbs.len() < len {
+            self.limbs.resize(len, 0);
+        }
+        let borrow = limbs::sub_assign(&mut self.limbs, &other.limbs[..len]);
+        if borrow > 0 {
+            // the limbs wrapped around to B^n - (other - self)
+            limbs::negate(&mut self.limbs);
+        }
+        self.trim();
+        borrow > 0
+    }
+
+    // self = self * m + a
+    pub fn mul_add_limb(&mut self, m: u64, a: u64) {
+        let mut carry = a;
+        for x in self.limbs.iter_mut() {
+            let (lo, hi) = long_mul(*x, m);
+            let (s, c) = lo.overflowing_add(carry);
+            *x = s;
+            // x * m + carry < 2^128, so this cannot overflow
+            carry = hi + c as u64;
+        }
+        if carry > 0 {
+            self.limbs.push(carry);
+        }
+        if m == 0 {
+            self.trim();
+        }
+        self.assert_invariants();
+    }
+
+    // writes the quotient into q and the remainder into r, reusing
+    // their buffers
+    pub fn divmod_into(&self, divisor: &BigUint, q: &mut BigUint, r: &mut BigUint,
+                       scratch: &mut Scratch) {
+        let divisor_len = limbs::trimmed_len(&divisor.limbs);
+        assert!(divisor_len > 0, "attempt to divide by zero");
+        if divisor_len == 1 {
+            set_limbs(&mut q.limbs, &self.limbs);
+            let rem = div::div_rem_limb(&mut q.limbs, divisor.limbs[0]);
+            q.trim();
+            set_limbs(&mut r.limbs, &[rem]);
+            return;
+        }
+        if limbs::cmp(&self.limbs, &divisor.limbs) == Less {
+            set_limbs(&mut q.limbs, &[0]);
+            set_limbs(&mut r.limbs, &self.limbs);
+            r.trim();
+            return;
+        }
+
+        let self_len = limbs::trimmed_len(&self.limbs);
+        div::div_rem_knuth_into(&self.limbs[..self_len], &divisor.limbs[..divisor_len],
+                                &mut q.limbs, &mut r.limbs, &mut scratch.buf);
+        q.trim();
+        r.trim();
+    }
+}
@@ -0,0 +1,140 @@
+// Signed arithmetic on top of the unsigned magnitudes.
+// Zero is always stored with a positive sign.
+
+use super::{BigUint,BigInt,IntSign,BigNumError};
+
+impl BigInt {
+    pub fn from_biguint(sign: IntSign, num: BigUint) -> BigInt {
+        let sign = if num.is_zero() { IntSign::Pos } else { sign };
+        BigInt { sign, num }
+    }
+
+    pub fn sign(&self) -> IntSign {
+        self.sign
+    }
+
+    pub fn magnitude(&self) -> &BigUint {
+        &self.num
+    }
+
+    pub fn into_parts(self) -> (IntSign, BigUint) {
+        (self.sign, self.num)
+    }
+
+    pub fn zero() -> BigInt {
+        BigInt::from_biguint(IntSign::Pos, BigUint::from_u64(0))
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.num.is_zero()
+    }
+
+    // panics if the magnitude is not canonical or zero is negative;
+    // does nothing in release builds
+    #[inline]
+    pub fn assert_invariants(&self) {
+        self.num.assert_invariants();
+        debug_assert!(self.sign == IntSign::Pos || !self.num.is_zero(), "negative zero");
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.sign == IntSign::Neg && !self.num.is_zero()
+    }
+
+    pub fn is_positive(&self) -> bool {
+        self.sign == IntSign::Pos && !self.num.is_zero()
+    }
+
+    pub fn abs(&self) -> BigInt {
+        BigInt::from_biguint(IntSign::Pos, self.num.clone())
+    }
+
+    // -1, 0 or 1
+    pub fn signum(&self) -> BigInt {
+        if self.is_zero() {
+            BigInt::zero()
+        } else {
+            BigInt::from_biguint(self.sign, BigUint::from_u64(1))
+        }
+    }
+
+    pub fn add(&self, other: &BigInt) -> BigInt {
+        if self.is_negative() == other.is_negative() {
+            return BigInt::from_biguint(sign_of(self), self.num.add(&other.num));
+        }
+        // the signs differ, so the result takes the sign of the bigger magnitude
+        let diff = self.num.signed_sub(&other.num);
+        let sign = if diff.sign == IntSign::Pos { sign_of(self) }
+                   else { sign_of(other) };
+        BigInt::from_biguint(sign, diff.num)
+    }
+
+    pub fn sub(&self, other: &BigInt) -> BigInt {
+        self.add(&-other)
+    }
+
+    pub fn mul(&self, other: &BigInt) -> BigInt {
+        BigInt::from_biguint(product_sign(self, other), self.num.mul(&other.num))
+    }
+
+    // truncated division: the quotient is rounded towards zero and
+    // the remainder has the sign of the dividend
+    pub fn div_rem(&self, other: &BigInt) -> (BigInt, BigInt) {
+        let (quot, rem) = self.num.divmod(&other.num);
+        (BigInt::from_biguint(product_sign(self, other), quot),
+         BigInt::from_biguint(self.sign, rem))
+    }
+
+    // floored division: the quotient is rounded towards negative infinity
+    // and the remainder has the sign of the divisor
+    pub fn div_mod_floor(&self, other: &BigInt) -> (BigInt, BigInt) {
+        let (quot, rem) = self.div_rem(other);
+        if !rem.is_zero() && rem.is_negative() != other.is_negative() {
+            (quot.sub(&BigInt::from_i64(1)), rem.add(other))
+        } else {
+            (quot, rem)
+        }
+    }
+
+    // truncated, like div_rem
+    pub fn checked_div(&self, other: &BigInt) -> Result<BigInt, BigNumError> {
+        if other.is_zero() {
+            return Err(BigNumError::DivisionByZero);
+        }
+        Ok(self.div_rem(other).0)
+    }
+
+    pub fn checked_rem(&self, other: &BigInt) -> Result<BigInt, BigNumError> {
+        if other.is_zero() {
+            return Err(BigNumError::DivisionByZero);
+        }
+        Ok(self.div_rem(other).1)
+    }
+
+    pub fn pow(&self, exp: u32) -> BigInt {
+        let sign = if exp % 2 == 1 { self.sign } else { IntSign::Pos };
+        BigInt::from_biguint(sign, self.num.pow(exp))
+    }
+}
+
+fn sign_of(a: &BigInt) -> IntSign {
+    if a.is_negative() { IntSign::Neg } else { IntSign::Pos }
+}
+
+fn product_sign(a: &BigInt, b: &BigInt) -> IntSign {
+    if a.is_negative() != b.is_negative() { IntSign::Neg } else { IntSign::Pos }
+}
+
+impl BigUint {
+    // square-and-multiply, from the top bit down
+    pub fn pow(&self, exp: u32) -> BigUint {
+        let mut result = BigUint::from_u64(1);
+        for i in (0..32 - exp.leading_zeros()).rev() {
+            result = result.square();
+            if exp & (1 << i) != 0 {
+                result = result.mul(self);
+            }
+        }
+        result
+    }
+}
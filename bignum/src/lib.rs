@@ -1,56 +1,103 @@
 #![crate_type = "lib"]
 #![crate_name = "bignum"]
 
-#![feature(asm)]
+extern crate bitvec;
+#[cfg(feature = "serde")]
+extern crate serde;
 
-use std::str;
-use std::cmp::Ordering;
 use std::cmp::Ordering::{Greater,Less,Equal};
 
-// unsigned
+use error::check_radix;
+
+mod limbs;
+mod mul;
+mod ntt;
+mod div;
+mod radix;
+mod ops;
+mod int;
+mod fmt;
+mod nt;
+mod prime;
+mod modular;
+mod inplace;
+mod bits;
+mod convert;
+mod rational;
+mod float;
+mod comb;
+mod encode;
+mod ct;
+mod random;
+mod par;
+mod error;
+mod uint;
+#[cfg(feature = "serde")]
+mod serde_impls;
+
+pub use modular::{MontgomeryCtx,BarrettCtx};
+pub use inplace::Scratch;
+pub use rational::BigRational;
+pub use float::{BigFloat,RoundingMode};
+pub use ct::{Choice,CtUint,CtMontgomery};
+pub use random::{Rng,Xoshiro256};
+pub use error::BigNumError;
+pub use uint::{UInt,U256,U512};
+#[cfg(feature = "parallel")]
+pub use par::{max_threads,set_max_threads};
+
+// unsigned; the limbs are little-endian, with no leading zero limbs
+// except for zero itself, which is a single zero limb
+#[derive(Clone)]
 pub struct BigUint {
-    pub limbs: Vec<u64>,
+    limbs: Vec<u64>,
 }
 
-#[derive(PartialEq,Clone)]
+#[derive(PartialEq,Eq,Clone,Copy,Debug)]
 pub enum IntSign {
     Pos,
     Neg,
 }
-// signed
+// signed; built through from_biguint, so zero is never negative
+#[derive(Clone)]
 pub struct BigInt {
-    pub sign: IntSign,
-    pub num: BigUint,
+    sign: IntSign,
+    num: BigUint,
 }
 
 pub const BITS_IN_LIMB: usize = 64;
-const ALPHABET: &'static str =
+const ALPHABET: &str =
     "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz+=";
 
+// returns (low, high) limbs of the product
+#[cfg(not(all(feature = "asm", target_arch = "x86_64")))]
 #[inline(always)]
 fn long_mul(a: u64, b: u64) -> (u64, u64) {
-    let mut _mul: u64;
-    let mut _overflow: u64;
+    let wide = (a as u128) * (b as u128);
+    (wide as u64, (wide >> 64) as u64)
+}
+
+#[cfg(all(feature = "asm", target_arch = "x86_64"))]
+#[inline(always)]
+fn long_mul(a: u64, b: u64) -> (u64, u64) {
+    let low: u64;
+    let high: u64;
     unsafe {
-        asm!("mul %rdx"
-            :"={rax}"(_mul), "={rdx}"(_overflow)
-            :"{rax}"(a), "{rdx}"(b));
+        ::std::arch::asm!("mul {b}",
+            b = in(reg) b,
+            inout("rax") a => low,
+            out("rdx") high,
+            options(pure, nomem, nostack));
     }
-    (_mul, _overflow)
+    (low, high)
 }
 
 impl BigInt {
     pub fn from_i64(n: i64) -> BigInt {
         if n >= 0 {
-            BigInt {
-                sign: IntSign::Pos,
-                num: BigUint::from_u64(n as u64)
-            }
+            BigInt::from_biguint(IntSign::Pos, BigUint::from_u64(n as u64))
         } else {
-            BigInt {
-                sign: IntSign::Neg,
-                num: BigUint::from_u64((-n) as u64)
-            }
+            BigInt::from_biguint(IntSign::Neg, BigUint::from_u64((n as u64).wrapping_neg()))
         }
     }
 
@@ -62,19 +109,12 @@ impl BigInt {
         self.num.dump();
     }
 
-    pub fn to_base_string(&self, base: u64) -> Result<String, &'static str> {
+    pub fn to_base_string(&self, base: u64) -> Result<String, BigNumError> {
         let prefix: String = (match self.sign {
                 IntSign::Pos => "",
                 IntSign::Neg => "-",
             }).to_string();
-        match self.num.to_base_string(base) {
-            Ok(s) => Ok(prefix + &s),
-            Err(e) => Err(e),
-        }
-    }
-
-    pub fn clone(&self) -> BigInt {
-        BigInt { sign: self.sign.clone(), num: self.num.clone() }
+        Ok(prefix + &self.num.to_base_string(base)?)
     }
 }
 
@@ -102,58 +142,28 @@ impl BigUint {
         }
     }
 
-    pub fn digits(&self, base: u64) -> Result<Vec<u8>, &'static str> {
-        if base < 2 || base > 256 {
-            return Err("The base has to be in the range [2, 256]");
-        }
-
-        let (step_u, digits_per_limb) =
-            BigUint::digit_limb_base(base);
-        let step = BigUint::from_u64(step_u);
-
-        let mut rest = self.clone();
-        let mut result: Vec<u8> = Vec::new();
-        while !rest.is_zero() {
-            let (quot, rem) = rest.divmod(&step);
-
-            let mut limb =
-                match rem.limbs.first() {
-                    None => 0,
-                    Some(x) => *x,
-                };
+    // digit values rather than characters, so any base up to 256 works
+    pub fn digits(&self, base: u64) -> Result<Vec<u8>, BigNumError> {
+        self.digits_with(base, &mut Scratch::new())
+    }
 
-            for _ in 0..digits_per_limb {
-                result.push((limb % base) as u8);
-                limb = limb / base;
-            }
+    // the same, dividing in buffers kept in the scratch, so a loop that
+    // prints many numbers reuses them instead of allocating
+    pub fn digits_with(&self, base: u64, scratch: &mut Scratch) -> Result<Vec<u8>, BigNumError> {
+        check_radix(base, 2, 256)?;
 
-            rest = quot;
+        if base.is_power_of_two() {
+            let bits = base.trailing_zeros() as usize;
+            return Ok(radix::to_digits_pow2(&self.limbs, bits));
         }
-
-        if result.len() == 0 {
-            result.push(0);
-        } else {
-            let mut i = result.len();
-            while result[i-1] == 0 && i > 1 {
-                i -= 1;
-            }
-            if i != result.len() {
-                result.truncate(i);
-            }
-        }
-
-        Ok(result)
+        let (step, digits_per_limb) = BigUint::digit_limb_base(base);
+        Ok(radix::to_digits(&self.limbs, base, step, digits_per_limb as usize, scratch))
     }
 
-    pub fn to_base_string(&self, base: u64) -> Result<String, &'static str> {
-        if base < 2 || base > 64 {
-            return Err("The base has to be in the range [2, 64]");
-        }
+    pub fn to_base_string(&self, base: u64) -> Result<String, BigNumError> {
+        check_radix(base, 2, 64)?;
 
-        let mut digs = match self.digits(base) {
-            Err(s) => return Err(s),
-            Ok(digs) => digs,
-        };
+        let mut digs = self.digits(base)?;
         digs.reverse();
 
         let alpha: &[u8] = ALPHABET.as_ref();
@@ -161,10 +171,33 @@ impl BigUint {
             digs[i] = alpha[digs[i] as usize];
         }
 
-        match str::from_utf8(&digs) {
-            Err(_) => Err("BigUint::to_base_string somehow produced invalid utf-8 bytes."),
-            Ok(s) => Ok(s.to_string())
+        Ok(String::from_utf8(digs).expect("the digit alphabet is ASCII"))
+    }
+
+    pub fn from_base_string(s: &str, base: u64) -> Result<BigUint, BigNumError> {
+        check_radix(base, 2, 64)?;
+        if s.is_empty() {
+            return Err(BigNumError::EmptyString);
         }
+
+        let alpha: &[u8] = ALPHABET.as_ref();
+        let mut digs = Vec::with_capacity(s.len());
+        for &c in s.as_bytes() {
+            // bases up to 36 are case-insensitive
+            let c = if base <= 36 { c.to_ascii_uppercase() } else { c };
+            match alpha[..base as usize].iter().position(|&a| a == c) {
+                None => return Err(BigNumError::InvalidDigit),
+                Some(d) => digs.push(d as u8),
+            }
+        }
+
+        let (step, digits_per_limb) = BigUint::digit_limb_base(base);
+        let limbs = radix::from_digits(&digs, base, step, digits_per_limb as usize);
+        Ok(BigUint::from_limbs(limbs))
+    }
+
+    pub fn from_str_radix(s: &str, radix: u32) -> Result<BigUint, BigNumError> {
+        BigUint::from_base_string(s, radix as u64)
     }
 
     pub fn from_u64(num: u64) -> BigUint {
@@ -172,103 +205,47 @@ impl BigUint {
     }
 
     pub fn is_zero(&self) -> bool {
-        if self.limbs.len() == 0 {
-            true
-        } else {
-            self.limbs.len() == 1 && self.limbs[0] == 0
-        }
+        limbs::trimmed_len(&self.limbs) == 0
     }
 
     pub fn add(&self, other: &BigUint) -> BigUint {
-        let mut result = BigUint { limbs: Vec::new() };
-        let (l1, l2) = (self.limbs.len(), other.limbs.len());
-        let (maxlen, minlen) =
-            if l1 > l2 { (l1, l2) }
-            else { (l2, l1) };
-        let (ref bigger, ref smaller) =
-            if l1 > l2 { (&self.limbs, &other.limbs) }
-            else { (&other.limbs, &self.limbs) };
-
-        let mut carry = 0;
-        for i in 0..minlen {
-            let mut limb = bigger[i].wrapping_add(carry);
-            carry = if limb < carry { 1 } else { 0 };
-            limb = limb.wrapping_add(smaller[i]);
-            carry = if carry > 0 || limb < smaller[i] { 1 } else { 0 };
-            result.limbs.push(limb);
-        }
-        for i in minlen..maxlen {
-            let limb = bigger[i].wrapping_add(carry);
-            carry = if limb < carry { 1 } else { 0 };
-            result.limbs.push(limb);
-        }
-        if carry > 0 {
-            result.limbs.push(carry);
-        }
-        result
+        BigUint::from_limbs(limbs::add_vec(&self.limbs, &other.limbs))
     }
 
+    // number of significant bits
     pub fn highest_u64_bit(n: u64) -> usize {
-        let mut tmp = n;
-        for i in 0..(BITS_IN_LIMB-1) {
-            if tmp == 0 {
-                return i;
-            }
-            tmp = tmp >> 1;
-        }
-        return BITS_IN_LIMB-1;
+        BITS_IN_LIMB - n.leading_zeros() as usize
     }
 
-    fn highest_bit(&self) -> usize {
-        if self.limbs.len() == 0 {
+    // number of significant bits
+    pub fn highest_bit(&self) -> usize {
+        let len = limbs::trimmed_len(&self.limbs);
+        if len == 0 {
             return 0;
         }
 
-        let guaranteed_bits = (self.limbs.len() - 1) * BITS_IN_LIMB;
-        match self.limbs.last() {
-            None => 0,
-            Some(last) => guaranteed_bits + BigUint::highest_u64_bit(*last),
-        }
+        let guaranteed_bits = (len - 1) * BITS_IN_LIMB;
+        guaranteed_bits + BigUint::highest_u64_bit(self.limbs[len - 1])
     }
 
     pub fn mul_by_limb(&mut self, n: u64) {
-        let ref mut limbs = self.limbs;
-        let mut carry: u64 = 0;
-        for i in 0..limbs.len() {
-            let (mul, over) = long_mul(limbs[i], n);
-            let limb = mul.wrapping_add(carry);
-            carry = if limb < carry { 1 } else { 0 };
-            limbs[i] = limb;
-            carry += over;
-        }
-        if carry > 0 {
-            limbs.push(carry);
-        }
+        self.mul_add_limb(n, 0);
     }
 
     pub fn bit_shift_right(&mut self, n: usize) {
         let old_len = self.limbs.len();
-        if old_len == 0 {
-            return;
-        }
-
         let limb_shift = n / BITS_IN_LIMB;
         let rest_bitshift = n % BITS_IN_LIMB;
-        let rest_invshift = BITS_IN_LIMB - n;
 
-        {
-            let ref mut limbs = self.limbs;
+        if rest_bitshift > 0 {
+            let rest_invshift = BITS_IN_LIMB - rest_bitshift;
+            let limbs = &mut self.limbs;
             for i in 0..(old_len-1) {
                 limbs[i] = (limbs[i] >> rest_bitshift) |
                            (limbs[i+1] << rest_invshift);
             }
 
-            let last_value = limbs[old_len-1] >> rest_bitshift;
-            if last_value > 0 {
-                limbs[old_len-1] = last_value;
-            } else {
-                let _ = limbs.pop();
-            }
+            limbs[old_len-1] >>= rest_bitshift;
         }
 
         self.limb_shift_right(limb_shift);
@@ -281,7 +258,7 @@ impl BigUint {
         let rest_invshift = (BITS_IN_LIMB as u32) - rest_bitshift;
 
         {
-            let ref mut limbs = self.limbs;
+            let limbs = &mut self.limbs;
             let has_last = match limbs.last() {
                     None => false,
                     Some(x) => x.wrapping_shr(rest_invshift) > 0,
@@ -306,121 +283,98 @@ impl BigUint {
         if limb_shift > 0 {
             self.limb_shift_left(limb_shift);
         }
+        self.trim();
     }
 
     pub fn limb_shift_left(&mut self, n: usize) {
         let old_len = self.limbs.len();
-        let ref mut limbs = self.limbs;
+        let limbs = &mut self.limbs;
         limbs.resize(old_len + n, 0);
 
-        for i in (0..old_len).rev() {
-            limbs[i + n] = limbs[i];
-        }
-
-        for i in 0..n {
-            limbs[i] = 0;
-        }
+        limbs.copy_within(0..old_len, n);
+        limbs[..n].fill(0);
+        self.trim();
     }
 
     pub fn limb_shift_right(&mut self, n: usize) {
-        let ref mut limbs = self.limbs;
+        let limbs = &mut self.limbs;
         let old_len = limbs.len();
-        let new_len = if old_len < n { 0 }
-                      else { old_len - n };
+        let new_len = old_len.saturating_sub(n);
 
-        for i in 0..new_len {
-            limbs[i] = limbs[i+n];
+        if new_len > 0 {
+            limbs.copy_within(n..old_len, 0);
         }
 
-        limbs.truncate(new_len)
+        limbs.truncate(new_len);
+        self.trim();
     }
 
-    pub fn mul(&self, other: &BigUint) -> BigUint {
-        let (l1, l2) = (self.limbs.len(), other.limbs.len());
-        let (_maxlen, minlen) =
-            if l1 > l2 { (l1, l2) }
-            else { (l2, l1) };
-        let (ref bigger, ref smaller) =
-            if l1 > l2 { (&self.limbs, &other.limbs) }
-            else { (&other.limbs, &self.limbs) };
-
-        let mut result = BigUint::from_u64(0);
-        for i in 0..minlen {
-            let mut copy = BigUint { limbs: (*bigger).clone() };
-            copy.limb_shift_left(i);
-            copy.mul_by_limb(smaller[i]);
-            result = result.add(&copy);
-        }
+    // takes little-endian limbs in any form; leading zeros are dropped
+    pub fn from_limbs(limbs: Vec<u64>) -> BigUint {
+        let mut result = BigUint { limbs };
+        result.trim();
         result
     }
 
-    pub fn cmp(&self, other: &BigUint) -> Ordering {
-        let (l1, l2) = (self.limbs.len(), other.limbs.len());
-        match l1.cmp(&l2) {
-            Greater => return Greater,
-            Less => return Less,
-            Equal => (),
-        }
+    // the little-endian limbs, in canonical form
+    pub fn limbs(&self) -> &[u64] {
+        &self.limbs
+    }
 
-        for i in (0..l1).rev() {
-            match self.limbs[i].cmp(&other.limbs[i]) {
-                Greater => return Greater,
-                Less => return Less,
-                Equal => (),
-            }
+    pub fn into_limbs(self) -> Vec<u64> {
+        self.limbs
+    }
+
+    // puts the limbs back into canonical form
+    fn trim(&mut self) {
+        let len = limbs::trimmed_len(&self.limbs);
+        self.limbs.truncate(len);
+        if self.limbs.is_empty() {
+            self.limbs.push(0);
         }
+    }
+
+    // panics if the limbs are not in canonical form; does nothing in
+    // release builds
+    #[inline]
+    pub fn assert_invariants(&self) {
+        debug_assert!(!self.limbs.is_empty(), "BigUint without limbs");
+        debug_assert!(self.limbs.len() == 1 || self.limbs[self.limbs.len() - 1] != 0,
+                      "BigUint with a leading zero limb: {:?}", self.limbs);
+    }
+
+    pub fn mul(&self, other: &BigUint) -> BigUint {
+        BigUint::from_limbs(mul::mul_limbs(&self.limbs, &other.limbs))
+    }
+
+    pub fn square(&self) -> BigUint {
+        BigUint::from_limbs(mul::sqr_limbs(&self.limbs))
+    }
 
-        Equal
+    // quadratic multiplication, mostly useful as a reference
+    pub fn mul_schoolbook(&self, other: &BigUint) -> BigUint {
+        let mut result = vec![0; self.limbs.len() + other.limbs.len()];
+        mul::schoolbook_into(&mut result, &self.limbs, &other.limbs);
+        BigUint::from_limbs(result)
     }
 
-    pub fn sub(&self, other: &BigUint) -> BigInt {
+    // self - other as a signed number; the `-` operator stays unsigned
+    // and panics when other is bigger
+    pub fn signed_sub(&self, other: &BigUint) -> BigInt {
         let cmp = self.cmp(other);
         if cmp == Equal {
-            return BigInt {
-                sign: IntSign::Pos,
-                num: BigUint::from_u64(0)
-            };
+            return BigInt::zero();
         }
 
         let (sign, bigger, smaller) =
-            match cmp {
-                Greater => (IntSign::Pos, &self, &other),
-                Less => (IntSign::Neg, &other, &self),
-                Equal => (IntSign::Pos, &other, &self),
-            };
-
-        let (maxlen, minlen) = (bigger.limbs.len(), smaller.limbs.len());
+            if cmp == Greater { (IntSign::Pos, &self, &other) }
+            else { (IntSign::Neg, &other, &self) };
 
         let mut result = bigger.limbs.clone();
-        let mut carry = 0;
-        for i in 0..minlen {
-            let limb_a = result[i].wrapping_sub(carry);
-            carry = if limb_a > result[i] { 1 } else { 0 };
-            let limb = limb_a.wrapping_sub(smaller.limbs[i]);
-            carry = if carry > 0 || limb > limb_a { 1 }
-                    else { 0 };
-            result[i] = limb;
-        }
-
-        for i in minlen..maxlen {
-            let limb = result[i].wrapping_sub(carry);
-            carry = if limb > result[i] { 1 } else { 0 };
-            result[i] = limb;
-        }
-
-        let l = result.len();
-        if l > 1 && result[l-1] == 0 {
-            result.truncate(l-1);
-        }
+        let minlen = limbs::trimmed_len(&smaller.limbs);
+        limbs::sub_assign(&mut result, &smaller.limbs[..minlen]);
 
-        BigInt {
-            sign: sign,
-            num: BigUint { limbs: result }
-        }
-    }
-
-    pub fn clone(&self) -> BigUint {
-        BigUint { limbs: self.limbs.clone() }
+        BigInt::from_biguint(sign, BigUint::from_limbs(result))
     }
 
     pub fn set_bit(&mut self, pos: usize) {
@@ -430,34 +384,46 @@ impl BigUint {
             self.limbs.resize(limb+1, 0);
         }
         self.limbs[limb] |= 1 << bit;
+        self.assert_invariants();
     }
 
-    // returns (quotient, remainder)
+    // returns (quotient, remainder); panics on a zero divisor, like the
+    // primitive types. Loops should keep a Scratch for divmod_into instead
     pub fn divmod(&self, divisor: &BigUint) -> (BigUint, BigUint) {
-        let self_bits = self.highest_bit();
-        let divisor_bits = divisor.highest_bit();
-        if self_bits < divisor_bits {
-            return (BigUint::from_u64(0), self.clone());
-        }
+        let mut quot = BigUint { limbs: Vec::new() };
+        let mut rem = BigUint { limbs: Vec::new() };
+        self.divmod_into(divisor, &mut quot, &mut rem, &mut Scratch::new());
+        (quot, rem)
+    }
 
-        let max_shift = self_bits - divisor_bits;
-        let mut tmp = self.clone();
-        let mut result = BigUint::from_u64(0);
-        let mut subtracting = divisor.clone();
-        subtracting.bit_shift_left(max_shift);
-
-        for i in (0..(max_shift+1)).rev() {
-            // TODO: this should call in-place subtraction
-            let sub = tmp.sub(&subtracting);
-            if sub.sign == IntSign::Pos {
-                result.set_bit(i);
-                tmp = sub.num;
-            }
+    // returns (quotient, remainder)
+    pub fn divmod_limb(&self, divisor: u64) -> (BigUint, u64) {
+        assert!(divisor != 0, "attempt to divide by zero");
+        let mut quot = self.limbs.clone();
+        let rem = div::div_rem_limb(&mut quot, divisor);
+        (BigUint::from_limbs(quot), rem)
+    }
+
+    pub fn checked_sub(&self, other: &BigUint) -> Result<BigUint, BigNumError> {
+        if limbs::cmp(&self.limbs, &other.limbs) == Less {
+            return Err(BigNumError::Negative);
+        }
+        Ok(BigUint::from_limbs(limbs::sub_vec(&self.limbs, &other.limbs)))
+    }
 
-            subtracting.bit_shift_right(1);
+    pub fn checked_divmod(&self, divisor: &BigUint) -> Result<(BigUint, BigUint), BigNumError> {
+        if divisor.is_zero() {
+            return Err(BigNumError::DivisionByZero);
         }
+        Ok(self.divmod(divisor))
+    }
+
+    pub fn checked_div(&self, divisor: &BigUint) -> Result<BigUint, BigNumError> {
+        self.checked_divmod(divisor).map(|(q, _)| q)
+    }
 
-        (result, tmp)
+    pub fn checked_rem(&self, divisor: &BigUint) -> Result<BigUint, BigNumError> {
+        self.checked_divmod(divisor).map(|(_, r)| r)
     }
 }
 
@@ -0,0 +1,177 @@
+// Low-level kernels over little-endian limb slices.
+// Apart from the *_vec helpers these never allocate; callers are
+// responsible for sizing the buffers.
+
+use std::cmp::Ordering;
+use std::cmp::Ordering::{Greater,Less,Equal};
+
+use super::long_mul;
+
+// returns (sum, carry); carry has to be 0 or 1
+#[cfg(not(all(feature = "asm", target_arch = "x86_64")))]
+#[inline(always)]
+pub fn add_carry(a: u64, b: u64, carry: u64) -> (u64, u64) {
+    let (s1, c1) = a.overflowing_add(b);
+    let (s2, c2) = s1.overflowing_add(carry);
+    (s2, (c1 | c2) as u64)
+}
+
+// returns (difference, borrow); borrow has to be 0 or 1
+#[cfg(not(all(feature = "asm", target_arch = "x86_64")))]
+#[inline(always)]
+pub fn sub_borrow(a: u64, b: u64, borrow: u64) -> (u64, u64) {
+    let (d1, b1) = a.overflowing_sub(b);
+    let (d2, b2) = d1.overflowing_sub(borrow);
+    (d2, (b1 | b2) as u64)
+}
+
+#[cfg(all(feature = "asm", target_arch = "x86_64"))]
+#[inline(always)]
+pub fn add_carry(a: u64, b: u64, carry: u64) -> (u64, u64) {
+    let mut sum = 0;
+    // older toolchains still declare the intrinsic unsafe
+    #[allow(unused_unsafe)]
+    let carry = unsafe {
+        ::std::arch::x86_64::_addcarry_u64(carry as u8, a, b, &mut sum)
+    };
+    (sum, carry as u64)
+}
+
+#[cfg(all(feature = "asm", target_arch = "x86_64"))]
+#[inline(always)]
+pub fn sub_borrow(a: u64, b: u64, borrow: u64) -> (u64, u64) {
+    let mut diff = 0;
+    // older toolchains still declare the intrinsic unsafe
+    #[allow(unused_unsafe)]
+    let borrow = unsafe {
+        ::std::arch::x86_64::_subborrow_u64(borrow as u8, a, b, &mut diff)
+    };
+    (diff, borrow as u64)
+}
+
+// number of limbs without the leading zeros
+pub fn trimmed_len(a: &[u64]) -> usize {
+    let mut len = a.len();
+    while len > 0 && a[len - 1] == 0 {
+        len -= 1;
+    }
+    len
+}
+
+pub fn cmp(a: &[u64], b: &[u64]) -> Ordering {
+    let (la, lb) = (trimmed_len(a), trimmed_len(b));
+    match la.cmp(&lb) {
+        Greater => return Greater,
+        Less => return Less,
+        Equal => (),
+    }
+    for i in (0..la).rev() {
+        match a[i].cmp(&b[i]) {
+            Greater => return Greater,
+            Less => return Less,
+            Equal => (),
+        }
+    }
+    Equal
+}
+
+// acc += b, where acc.len() >= b.len(); returns the carry out of acc
+pub fn add_assign(acc: &mut [u64], b: &[u64]) -> u64 {
+    debug_assert!(acc.len() >= b.len());
+    let mut carry = 0;
+    for (x, &y) in acc.iter_mut().zip(b.iter()) {
+        let (s, c) = add_carry(*x, y, carry);
+        *x = s;
+        carry = c;
+    }
+    for x in acc[b.len()..].iter_mut() {
+        if carry == 0 {
+            break;
+        }
+        let (s, c) = x.overflowing_add(carry);
+        *x = s;
+        carry = c as u64;
+    }
+    carry
+}
+
+// acc -= b, where acc.len() >= b.len(); returns the borrow out of acc
+pub fn sub_assign(acc: &mut [u64], b: &[u64]) -> u64 {
+    debug_assert!(acc.len() >= b.len());
+    let mut borrow = 0;
+    for (x, &y) in acc.iter_mut().zip(b.iter()) {
+        let (d, c) = sub_borrow(*x, y, borrow);
+        *x = d;
+        borrow = c;
+    }
+    for x in acc[b.len()..].iter_mut() {
+        if borrow == 0 {
+            break;
+        }
+        let (d, c) = x.overflowing_sub(borrow);
+        *x = d;
+        borrow = c as u64;
+    }
+    borrow
+}
+
+// acc[..a.len()] += a * b; returns the carry limb
+pub fn mac_limb(acc: &mut [u64], a: &[u64], b: u64) -> u64 {
+    debug_assert!(acc.len() >= a.len());
+    let mut carry = 0;
+    for (x, &y) in acc.iter_mut().zip(a.iter()) {
+        let (lo, hi) = long_mul(y, b);
+        let (s1, c1) = x.overflowing_add(lo);
+        let (s2, c2) = s1.overflowing_add(carry);
+        *x = s2;
+        // x + y * b + carry < 2^128, so this cannot overflow
+        carry = hi + c1 as u64 + c2 as u64;
+    }
+    carry
+}
+
+// acc[..a.len()] -= a * b; returns the borrow limb
+pub fn submul_limb(acc: &mut [u64], a: &[u64], b: u64) -> u64 {
+    debug_assert!(acc.len() >= a.len());
+    let mut borrow = 0;
+    for (x, &y) in acc.iter_mut().zip(a.iter()) {
+        let (lo, hi) = long_mul(y, b);
+        let (d1, b1) = x.overflowing_sub(lo);
+        let (d2, b2) = d1.overflowing_sub(borrow);
+        *x = d2;
+        borrow = hi + b1 as u64 + b2 as u64;
+    }
+    borrow
+}
+
+// a = B^n - a, the two's complement negation of a
+pub fn negate(a: &mut [u64]) {
+    for x in a.iter_mut() {
+        *x = !*x;
+    }
+    let mut carry = 1;
+    for x in a.iter_mut() {
+        if carry == 0 {
+            break;
+        }
+        let (s, c) = x.overflowing_add(carry);
+        *x = s;
+        carry = c as u64;
+    }
+}
+
+pub fn add_vec(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let (a, b) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let mut result = Vec::with_capacity(a.len() + 1);
+    result.extend_from_slice(a);
+    result.push(0);
+    add_assign(&mut result, b);
+    result
+}
+
+// a - b, where a >= b
+pub fn sub_vec(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let mut result = a.to_vec();
+    sub_assign(&mut result, &b[..trimmed_len(b)]);
+    result
+}
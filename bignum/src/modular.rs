@@ -0,0 +1,136 @@
+// Contexts for repeated arithmetic modulo a fixed number: Montgomery
+// multiplication for odd moduli and Barrett reduction for any modulus.
+
+use std::borrow::Cow;
+use std::cmp::Ordering::Less;
+
+use super::BigUint;
+use div;
+use limbs;
+use mul;
+use nt::window_pow;
+
+pub struct MontgomeryCtx {
+    modulus: Vec<u64>,
+    // -modulus^-1 mod 2^64
+    m_inv: u64,
+    // R mod modulus and R^2 mod modulus, R = 2^(64 * modulus.len())
+    one: BigUint,
+    r2: BigUint,
+}
+
+impl MontgomeryCtx {
+    pub fn new(modulus: &BigUint) -> MontgomeryCtx {
+        assert!(modulus.test_bit(0), "Montgomery arithmetic needs an odd modulus");
+        let len = limbs::trimmed_len(&modulus.limbs);
+        let m = modulus.limbs[..len].to_vec();
+
+        // Newton's iteration doubles the correct low bits every step
+        let mut inv: u64 = 1;
+        for _ in 0..6 {
+            inv = inv.wrapping_mul(2u64.wrapping_sub(m[0].wrapping_mul(inv)));
+        }
+
+        let r = BigUint::from_u64(1) << (64 * len);
+        let one = &r % modulus;
+        let r2 = &one.square() % modulus;
+        MontgomeryCtx { modulus: m, m_inv: inv.wrapping_neg(), one, r2 }
+    }
+
+    pub fn modulus(&self) -> BigUint {
+        BigUint { limbs: self.modulus.clone() }
+    }
+
+    // x itself if it is already below the modulus
+    fn reduced<'a>(&self, x: &'a BigUint) -> Cow<'a, BigUint> {
+        if limbs::cmp(&x.limbs, &self.modulus) == Less { Cow::Borrowed(x) }
+        else { Cow::Owned(x % &self.modulus()) }
+    }
+
+    // x * R^-1 mod modulus, for x < modulus * R
+    fn redc(&self, mut t: Vec<u64>) -> BigUint {
+        let n = self.modulus.len();
+        t.resize(2 * n + 1, 0);
+        for i in 0..n {
+            let u = t[i].wrapping_mul(self.m_inv);
+            let carry = limbs::mac_limb(&mut t[i..i + n], &self.modulus, u);
+            limbs::add_assign(&mut t[i + n..], &[carry]);
+        }
+        let mut result = t.split_off(n);
+        if limbs::cmp(&result, &self.modulus) != Less {
+            limbs::sub_assign(&mut result, &self.modulus);
+        }
+        BigUint::from_limbs(result)
+    }
+
+    // x * R mod modulus
+    pub fn to_montgomery(&self, x: &BigUint) -> BigUint {
+        self.mul(x, &self.r2)
+    }
+
+    // x * R^-1 mod modulus
+    pub fn from_montgomery(&self, x: &BigUint) -> BigUint {
+        self.redc(self.reduced(x).limbs.clone())
+    }
+
+    // the product of two numbers in Montgomery form, in Montgomery form;
+    // inputs at or above the modulus are reduced first
+    pub fn mul(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        self.redc(mul::mul_limbs(&self.reduced(a).limbs, &self.reduced(b).limbs))
+    }
+
+    pub fn square(&self, a: &BigUint) -> BigUint {
+        self.redc(mul::sqr_limbs(&self.reduced(a).limbs))
+    }
+
+    // a^exp, with a and the result in Montgomery form
+    pub fn pow(&self, a: &BigUint, exp: &BigUint) -> BigUint {
+        window_pow(a.clone(), exp, self.one.clone(),
+                   |x, y| self.mul(x, y), |x| self.square(x))
+    }
+}
+
+pub struct BarrettCtx {
+    modulus: Vec<u64>,
+    // floor(B^2n / modulus)
+    mu: Vec<u64>,
+}
+
+impl BarrettCtx {
+    pub fn new(modulus: &BigUint) -> BarrettCtx {
+        assert!(!modulus.is_zero(), "attempt to calculate the remainder with a divisor of zero");
+        let len = limbs::trimmed_len(&modulus.limbs);
+        let m = modulus.limbs[..len].to_vec();
+        let mu = div::reciprocal(&m);
+        BarrettCtx { modulus: m, mu }
+    }
+
+    pub fn modulus(&self) -> BigUint {
+        BigUint { limbs: self.modulus.clone() }
+    }
+
+    // x mod modulus; x below B^2n takes the Barrett path, anything larger
+    // falls back to long division
+    pub fn reduce(&self, x: &BigUint) -> BigUint {
+        if limbs::trimmed_len(&x.limbs) > 2 * self.modulus.len() {
+            return x % &self.modulus();
+        }
+        let (_, rem) = div::div_rem_barrett(&x.limbs, &self.modulus, &self.mu);
+        BigUint::from_limbs(rem)
+    }
+
+    pub fn mul(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        self.reduce(&a.mul(b))
+    }
+
+    pub fn square(&self, a: &BigUint) -> BigUint {
+        self.reduce(&a.square())
+    }
+
+    pub fn pow(&self, a: &BigUint, exp: &BigUint) -> BigUint {
+        let one = self.reduce(&BigUint::from_u64(1));
+        let a = if limbs::cmp(&a.limbs, &self.modulus) == Less { a.clone() }
+                else { a % &self.modulus() };
+        window_pow(a, exp, one, |x, y| self.mul(x, y), |x| self.square(x))
+    }
+}
@@ -0,0 +1,257 @@
+// Multiplication: schoolbook, Karatsuba, Toom-3 and number-theoretic
+// transforms over limb slices. All the entry points write a product of
+// a.len() + b.len() limbs into a zeroed output buffer.
+
+use std::cmp::{min,Ordering};
+
+use super::long_mul;
+use limbs;
+use ntt::{self,NTT_THRESHOLD,NTT_SQR_THRESHOLD};
+use par::{self,PAR_THRESHOLD};
+
+// operand sizes (in limbs) where the recursive algorithms start to win
+pub const KARATSUBA_THRESHOLD: usize = 32;
+pub const TOOM3_THRESHOLD: usize = 160;
+pub const KARATSUBA_SQR_THRESHOLD: usize = 48;
+pub const TOOM3_SQR_THRESHOLD: usize = 192;
+
+pub fn mul_limbs(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let mut result = vec![0; a.len() + b.len()];
+    mul_into(&mut result, a, b);
+    result
+}
+
+pub fn sqr_limbs(a: &[u64]) -> Vec<u64> {
+    let mut result = vec![0; 2 * a.len()];
+    sqr_into(&mut result, a);
+    result
+}
+
+pub fn schoolbook_into(r: &mut [u64], a: &[u64], b: &[u64]) {
+    for (i, &limb) in b.iter().enumerate() {
+        let carry = limbs::mac_limb(&mut r[i..], a, limb);
+        r[i + a.len()] = carry;
+    }
+}
+
+pub fn schoolbook_sqr_into(r: &mut [u64], a: &[u64]) {
+    let n = a.len();
+    // off-diagonal products a[i]*a[j] for i < j, counted once
+    for i in 0..n {
+        let carry = limbs::mac_limb(&mut r[2 * i + 1..], &a[i + 1..], a[i]);
+        r[i + n] = carry;
+    }
+    // double them
+    let mut top = 0;
+    for x in r.iter_mut() {
+        let next = *x >> 63;
+        *x = (*x << 1) | top;
+        top = next;
+    }
+    // and add the squares on the diagonal
+    let mut carry = 0;
+    for i in 0..n {
+        let (lo, hi) = long_mul(a[i], a[i]);
+        let (s, c) = limbs::add_carry(r[2 * i], lo, carry);
+        r[2 * i] = s;
+        let (s, c) = limbs::add_carry(r[2 * i + 1], hi, c);
+        r[2 * i + 1] = s;
+        carry = c;
+    }
+}
+
+pub fn mul_into(r: &mut [u64], a: &[u64], b: &[u64]) {
+    let (a, b) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let (la, lb) = (a.len(), b.len());
+    if lb == 0 {
+        return;
+    }
+    if lb < KARATSUBA_THRESHOLD {
+        schoolbook_into(r, a, b);
+        return;
+    }
+
+    // split the bigger operand into lb-sized chunks when the operands
+    // are too unbalanced for the recursive algorithms to pay off
+    if 2 * lb <= la && par::ENABLED && la >= PAR_THRESHOLD {
+        // two halves on their own threads, each chunked in turn
+        let mid = la.div_ceil(lb) / 2 * lb;
+        let (a_lo, a_hi) = a.split_at(mid);
+        let (lo, hi) = par::join(true, || mul_limbs(a_lo, b), || mul_limbs(a_hi, b));
+        r[..lo.len()].copy_from_slice(&lo);
+        limbs::add_assign(&mut r[mid..], &hi[..limbs::trimmed_len(&hi)]);
+        return;
+    }
+    if 2 * lb <= la {
+        let mut tmp = vec![0; 2 * lb];
+        let mut start = 0;
+        while start < la {
+            let chunk = &a[start..min(start + lb, la)];
+            let len = chunk.len() + lb;
+            for x in tmp.iter_mut() {
+                *x = 0;
+            }
+            mul_into(&mut tmp[..len], chunk, b);
+            limbs::add_assign(&mut r[start..], &tmp[..len]);
+            start += lb;
+        }
+        return;
+    }
+
+    if lb >= NTT_THRESHOLD {
+        ntt::mul_ntt(r, a, b, false);
+    } else if lb >= TOOM3_THRESHOLD && lb > 2 * la.div_ceil(3) {
+        toom3(r, a, b, false);
+    } else {
+        karatsuba(r, a, b, false);
+    }
+}
+
+pub fn sqr_into(r: &mut [u64], a: &[u64]) {
+    let n = a.len();
+    if n < KARATSUBA_SQR_THRESHOLD {
+        schoolbook_sqr_into(r, a);
+    } else if n < TOOM3_SQR_THRESHOLD {
+        karatsuba(r, a, a, true);
+    } else if n < NTT_SQR_THRESHOLD {
+        toom3(r, a, a, true);
+    } else {
+        ntt::mul_ntt(r, a, a, true);
+    }
+}
+
+fn product(a: &[u64], b: &[u64], square: bool) -> Vec<u64> {
+    if square { sqr_limbs(a) } else { mul_limbs(a, b) }
+}
+
+fn product_into(r: &mut [u64], a: &[u64], b: &[u64], square: bool) {
+    if square { sqr_into(r, a) } else { mul_into(r, a, b) }
+}
+
+// a*b = z2*B^2h + z1*B^h + z0, z1 = (a0 + a1)*(b0 + b1) - z0 - z2
+fn karatsuba(r: &mut [u64], a: &[u64], b: &[u64], square: bool) {
+    let (la, lb) = (a.len(), b.len());
+    let h = la.div_ceil(2);
+    let (a0, a1) = a.split_at(h);
+    let (b0, b1) = b.split_at(min(h, lb));
+
+    // the three half-size products are independent
+    let split = lb >= PAR_THRESHOLD;
+    let (r0, r2) = r.split_at_mut(a0.len() + b0.len());
+    let r2 = &mut r2[..a1.len() + b1.len()];
+    let (_, mut z1) = par::join(split, || {
+        par::join(split, || product_into(r0, a0, b0, square),
+                  || product_into(r2, a1, b1, square))
+    }, || {
+        let sa = limbs::add_vec(a0, a1);
+        if square { sqr_limbs(&sa) } else { mul_limbs(&sa, &limbs::add_vec(b0, b1)) }
+    });
+    limbs::sub_assign(&mut z1, &r[..a0.len() + b0.len()]);
+    limbs::sub_assign(&mut z1, &r[a0.len() + b0.len()..la + lb]);
+    let len = limbs::trimmed_len(&z1);
+    limbs::add_assign(&mut r[h..], &z1[..len]);
+}
+
+// a signed number used for the Toom-3 intermediate values
+struct Signed {
+    neg: bool,
+    mag: Vec<u64>,
+}
+
+impl Signed {
+    fn new(mag: Vec<u64>) -> Signed {
+        Signed { neg: false, mag }
+    }
+
+    fn add(&self, other: &Signed) -> Signed {
+        if self.neg == other.neg {
+            return Signed { neg: self.neg, mag: limbs::add_vec(&self.mag, &other.mag) };
+        }
+        match limbs::cmp(&self.mag, &other.mag) {
+            Ordering::Less => Signed {
+                neg: other.neg,
+                mag: limbs::sub_vec(&other.mag, &self.mag),
+            },
+            _ => Signed {
+                neg: self.neg,
+                mag: limbs::sub_vec(&self.mag, &other.mag),
+            },
+        }
+    }
+
+    fn sub(&self, other: &Signed) -> Signed {
+        let negated = Signed { neg: !other.neg, mag: other.mag.clone() };
+        self.add(&negated)
+    }
+
+    fn double(&self) -> Signed {
+        self.add(self)
+    }
+
+    fn mul(&self, other: &Signed, square: bool) -> Signed {
+        let (a, b) = (&self.mag[..limbs::trimmed_len(&self.mag)],
+                      &other.mag[..limbs::trimmed_len(&other.mag)]);
+        Signed { neg: self.neg != other.neg, mag: product(a, b, square) }
+    }
+
+    // exact division by a small number
+    fn div_exact(&self, d: u64) -> Signed {
+        let mut mag = self.mag.clone();
+        let mut rem: u64 = 0;
+        for x in mag.iter_mut().rev() {
+            let cur = ((rem as u128) << 64) | (*x as u128);
+            *x = (cur / d as u128) as u64;
+            rem = (cur % d as u128) as u64;
+        }
+        debug_assert!(rem == 0);
+        Signed { neg: self.neg, mag }
+    }
+
+    fn unsigned(self) -> Vec<u64> {
+        debug_assert!(!self.neg || limbs::trimmed_len(&self.mag) == 0);
+        self.mag
+    }
+}
+
+// evaluate at 0, 1, -1, -2, inf and interpolate (Bodrato's sequence)
+pub fn toom3(r: &mut [u64], a: &[u64], b: &[u64], square: bool) {
+    let k = a.len().div_ceil(3);
+    let split = |x: &[u64]| {
+        let (x0, rest) = x.split_at(min(k, x.len()));
+        let (x1, x2) = rest.split_at(min(k, rest.len()));
+        (Signed::new(x0.to_vec()), Signed::new(x1.to_vec()), Signed::new(x2.to_vec()))
+    };
+    let (a0, a1, a2) = split(a);
+    let (b0, b1, b2) = split(b);
+
+    let a02 = a0.add(&a2);
+    let (p1, pm1) = (a02.add(&a1), a02.sub(&a1));
+    let pm2 = pm1.add(&a2).double().sub(&a0);
+    let b02 = b0.add(&b2);
+    let (q1, qm1) = (b02.add(&b1), b02.sub(&b1));
+    let qm2 = qm1.add(&b2).double().sub(&b0);
+
+    // and the five third-size ones too
+    let split = b.len() >= PAR_THRESHOLD;
+    let ((r0, r1), ((rm1, rm2), rinf)) = par::join(split, || {
+        par::join(split, || a0.mul(&b0, square), || p1.mul(&q1, square))
+    }, || {
+        par::join(split, || {
+            par::join(split, || pm1.mul(&qm1, square), || pm2.mul(&qm2, square))
+        }, || a2.mul(&b2, square))
+    });
+
+    let c3 = rm2.sub(&r1).div_exact(3);
+    let c1 = r1.sub(&rm1).div_exact(2);
+    let c2 = rm1.sub(&r0);
+    let c3 = c2.sub(&c3).div_exact(2).add(&rinf.double());
+    let c2 = c2.add(&c1).sub(&rinf);
+    let c1 = c1.sub(&c3);
+
+    let coeffs = [r0.unsigned(), c1.unsigned(), c2.unsigned(),
+                  c3.unsigned(), rinf.unsigned()];
+    for (i, c) in coeffs.iter().enumerate() {
+        let len = limbs::trimmed_len(c);
+        limbs::add_assign(&mut r[i * k..], &c[..len]);
+    }
+}
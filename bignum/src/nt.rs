@@ -0,0 +1,187 @@
+// Number theory: gcd, modular exponentiation and inverses, integer roots.
+
+use std::cmp::Ordering::Less;
+use std::mem;
+
+use super::{BigUint,BigInt,IntSign,BITS_IN_LIMB};
+use limbs;
+use modular::MontgomeryCtx;
+
+// base^exp with a sliding window over the exponent bits, for any
+// multiplication that one is the identity of
+pub fn window_pow<M, S>(base: BigUint, exp: &BigUint, one: BigUint, mul: M, square: S)
+    -> BigUint
+    where M: Fn(&BigUint, &BigUint) -> BigUint, S: Fn(&BigUint) -> BigUint {
+    let bits = exp.bits();
+    if bits == 0 {
+        return one;
+    }
+    let window = if bits <= 24 { 1 }
+                 else if bits <= 80 { 3 }
+                 else if bits <= 240 { 4 }
+                 else if bits <= 672 { 5 }
+                 else { 6 };
+
+    // odd powers base^1, base^3, ..., base^(2^window - 1)
+    let base_sq = square(&base);
+    let mut table = vec![base];
+    for i in 1..(1 << (window - 1)) {
+        let next = mul(&table[i - 1], &base_sq);
+        table.push(next);
+    }
+
+    let mut result = one;
+    let mut i = bits;
+    while i > 0 {
+        if !exp.test_bit(i - 1) {
+            result = square(&result);
+            i -= 1;
+            continue;
+        }
+        // the longest window starting at bit i-1 that ends with a one
+        let mut low = i.saturating_sub(window);
+        while !exp.test_bit(low) {
+            low += 1;
+        }
+        let mut value = 0;
+        for j in (low..i).rev() {
+            result = square(&result);
+            value = (value << 1) | exp.test_bit(j) as usize;
+        }
+        result = mul(&result, &table[value >> 1]);
+        i = low;
+    }
+    result
+}
+
+impl BigUint {
+    // same as highest_bit
+    pub fn bits(&self) -> usize {
+        self.highest_bit()
+    }
+
+    // None for zero
+    pub fn trailing_zeros(&self) -> Option<usize> {
+        for (i, &limb) in self.limbs.iter().enumerate() {
+            if limb != 0 {
+                return Some(i * BITS_IN_LIMB + limb.trailing_zeros() as usize);
+            }
+        }
+        None
+    }
+
+    // binary gcd, with a Euclidean step whenever the operands differ
+    // in size by more than a limb
+    pub fn gcd(&self, other: &BigUint) -> BigUint {
+        let (mut a, mut b) = (self.clone(), other.clone());
+        let (za, zb) = match (a.trailing_zeros(), b.trailing_zeros()) {
+            (None, _) => return b,
+            (_, None) => return a,
+            (Some(za), Some(zb)) => (za, zb),
+        };
+        let shift = if za < zb { za } else { zb };
+        a >>= za;
+        b >>= zb;
+
+        // both are odd from here on
+        loop {
+            if a > b {
+                mem::swap(&mut a, &mut b);
+            }
+            let a_len = limbs::trimmed_len(&a.limbs);
+            if limbs::trimmed_len(&b.limbs) > a_len + 1 {
+                b = b.divmod(&a).1;
+            } else {
+                limbs::sub_assign(&mut b.limbs, &a.limbs[..a_len]);
+            }
+            match b.trailing_zeros() {
+                None => break,
+                Some(zb) => b >>= zb,
+            }
+        }
+        a << shift
+    }
+
+    pub fn lcm(&self, other: &BigUint) -> BigUint {
+        if self.is_zero() || other.is_zero() {
+            return BigUint::from_u64(0);
+        }
+        &(self / &self.gcd(other)) * other
+    }
+
+    // self^exp mod modulus; odd moduli go through Montgomery arithmetic
+    pub fn mod_pow(&self, exp: &BigUint, modulus: &BigUint) -> BigUint {
+        if modulus.test_bit(0) && modulus.bits() > 1 {
+            let ctx = MontgomeryCtx::new(modulus);
+            return ctx.from_montgomery(&ctx.pow(&ctx.to_montgomery(self), exp));
+        }
+        let reduce = |x: BigUint| x.divmod(modulus).1;
+        window_pow(reduce(self.clone()), exp, reduce(BigUint::from_u64(1)),
+                   |a, b| reduce(a * b), |a| reduce(a.square()))
+    }
+
+    // x such that self * x == 1 mod modulus, if it exists
+    pub fn mod_inverse(&self, modulus: &BigUint) -> Option<BigUint> {
+        let a = BigInt::from_biguint(IntSign::Pos, self.divmod(modulus).1);
+        let m = BigInt::from_biguint(IntSign::Pos, modulus.clone());
+        let (g, x, _) = a.extended_gcd(&m);
+        if g.num != BigUint::from_u64(1) {
+            return None;
+        }
+        Some(x.div_mod_floor(&m).1.num)
+    }
+
+    // floor(sqrt(self))
+    pub fn isqrt(&self) -> BigUint {
+        self.nth_root(2)
+    }
+
+    // floor(self^(1/n)), by Newton's iteration from an overestimate
+    pub fn nth_root(&self, n: u32) -> BigUint {
+        assert!(n > 0, "zeroth root is undefined");
+        if self.is_zero() || n == 1 {
+            return self.clone();
+        }
+        let bits = self.bits();
+        let n_big = n as usize;
+        let mut x = BigUint::from_u64(1) << bits.div_ceil(n_big);
+        loop {
+            // next = ((n - 1) * x + self / x^(n - 1)) / n
+            let mut next = &x * (n as u64 - 1);
+            next += self / &x.pow(n - 1);
+            next /= n as u64;
+            if next.cmp(&x) != Less {
+                return x;
+            }
+            x = next;
+        }
+    }
+}
+
+impl BigInt {
+    // returns (g, x, y) such that self*x + other*y == g == gcd(self, other)
+    pub fn extended_gcd(&self, other: &BigInt) -> (BigInt, BigInt, BigInt) {
+        let (mut old_r, mut r) = (self.abs(), other.abs());
+        let (mut old_s, mut s) = (BigInt::from_i64(1), BigInt::zero());
+        let (mut old_t, mut t) = (BigInt::zero(), BigInt::from_i64(1));
+        while !r.is_zero() {
+            let (q, rem) = old_r.div_rem(&r);
+            old_r = mem::replace(&mut r, rem);
+            let next_s = &old_s - &(&q * &s);
+            old_s = mem::replace(&mut s, next_s);
+            let next_t = &old_t - &(&q * &t);
+            old_t = mem::replace(&mut t, next_t);
+        }
+        if self.is_negative() {
+            old_s = -old_s;
+        }
+        if other.is_negative() {
+            old_t = -old_t;
+        }
+        (old_r, old_s, old_t)
+    }
+
+    pub fn gcd(&self, other: &BigInt) -> BigInt {
+        BigInt::from_biguint(IntSign::Pos, self.num.gcd(&other.num))
+    }
+}
@@ -0,0 +1,306 @@
+// Multiplication by number-theoretic transforms, for operands far past
+// the Toom-3 range. The limbs are convolved modulo three primes of the
+// form c * 2^k + 1 below 2^63, and since every coefficient of the exact
+// convolution is below n * 2^128 < p1 * p2 * p3, the CRT gets it back.
+// All of it is integer arithmetic, in Montgomery form modulo each prime.
+
+use std::sync::OnceLock;
+
+use par;
+
+// operand sizes (in limbs) where the transforms beat Toom-3, read off
+// the thresholds test below on one core of a Xeon, serial build: Toom-3
+// still wins at 40000 limbs (about 90ms against 140ms, and 75ms against
+// 110ms squared), the two are even at 50000 and the transforms win by
+// a fifth at 60000. Squares cross over at the same size, so the two
+// thresholds are equal.
+pub const NTT_THRESHOLD: usize = 50000;
+pub const NTT_SQR_THRESHOLD: usize = 50000;
+
+// (p, a generator of the multiplicative group, the power of two in p - 1)
+const PRIMES: [(u64, u64, u32); 3] = [
+    (0x3A00000000000001, 3, 57),
+    (0x5700000000000001, 5, 56),
+    (0x1B00000000000001, 5, 56),
+];
+
+struct Prime {
+    p: u64,
+    // -p^-1 mod 2^64
+    neg_inv: u64,
+    // R^2 mod p, R = 2^64
+    r2: u64,
+    generator: u64,
+    max_log: u32,
+}
+
+fn pow_mod(mut base: u64, mut exp: u64, p: u64) -> u64 {
+    let mut result = 1u64;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = (result as u128 * base as u128 % p as u128) as u64;
+        }
+        base = (base as u128 * base as u128 % p as u128) as u64;
+        exp >>= 1;
+    }
+    result
+}
+
+impl Prime {
+    fn new((p, generator, max_log): (u64, u64, u32)) -> Prime {
+        let mut inv: u64 = 1;
+        for _ in 0..6 {
+            inv = inv.wrapping_mul(2u64.wrapping_sub(p.wrapping_mul(inv)));
+        }
+        let r = (u64::MAX % p + 1) % p;
+        let r2 = (r as u128 * r as u128 % p as u128) as u64;
+        Prime { p, neg_inv: inv.wrapping_neg(), r2, generator, max_log }
+    }
+
+    // t * R^-1 mod p, for t < p * R
+    #[inline]
+    fn redc(&self, t: u128) -> u64 {
+        let m = (t as u64).wrapping_mul(self.neg_inv);
+        let u = ((t + m as u128 * self.p as u128) >> 64) as u64;
+        if u >= self.p { u - self.p } else { u }
+    }
+
+    #[inline]
+    fn mul(&self, a: u64, b: u64) -> u64 {
+        self.redc(a as u128 * b as u128)
+    }
+
+    #[inline]
+    fn add(&self, a: u64, b: u64) -> u64 {
+        let s = a + b;
+        if s >= self.p { s - self.p } else { s }
+    }
+
+    #[inline]
+    fn sub(&self, a: u64, b: u64) -> u64 {
+        if a >= b { a - b } else { a + self.p - b }
+    }
+
+    // x * R mod p, for any x
+    fn to_mont(&self, x: u64) -> u64 {
+        self.mul(x, self.r2)
+    }
+
+    // w^j for j < n/2, in Montgomery form, w a primitive n-th root of
+    // unity or its inverse
+    fn roots(&self, n: usize, inverse: bool) -> Vec<u64> {
+        assert!(n.trailing_zeros() <= self.max_log, "NTT length out of range for the primes");
+        let mut w = pow_mod(self.generator, (self.p - 1) / n as u64, self.p);
+        if inverse {
+            w = pow_mod(w, self.p - 2, self.p);
+        }
+        let w = self.to_mont(w);
+        let mut roots = Vec::with_capacity(n / 2);
+        let mut x = self.to_mont(1);
+        for _ in 0..n / 2 {
+            roots.push(x);
+            x = self.mul(x, w);
+        }
+        roots
+    }
+
+    // decimation in frequency: natural order in, bit-reversed order out
+    fn forward(&self, a: &mut [u64], roots: &[u64]) {
+        let n = a.len();
+        let mut len = n;
+        while len >= 2 {
+            let (half, stride) = (len / 2, n / len);
+            for chunk in a.chunks_mut(len) {
+                let (lo, hi) = chunk.split_at_mut(half);
+                for (j, (x, y)) in lo.iter_mut().zip(hi.iter_mut()).enumerate() {
+                    let (u, v) = (*x, *y);
+                    *x = self.add(u, v);
+                    *y = self.mul(self.sub(u, v), roots[j * stride]);
+                }
+            }
+            len = half;
+        }
+    }
+
+    // decimation in time: bit-reversed order in, natural order out
+    fn inverse(&self, a: &mut [u64], roots: &[u64]) {
+        let n = a.len();
+        let mut len = 2;
+        while len <= n {
+            let (half, stride) = (len / 2, n / len);
+            for chunk in a.chunks_mut(len) {
+                let (lo, hi) = chunk.split_at_mut(half);
+                for (j, (x, y)) in lo.iter_mut().zip(hi.iter_mut()).enumerate() {
+                    let (u, v) = (*x, self.mul(*y, roots[j * stride]));
+                    *x = self.add(u, v);
+                    *y = self.sub(u, v);
+                }
+            }
+            len *= 2;
+        }
+    }
+
+    // the cyclic convolution of a and b modulo p, of length n, in the
+    // normal form
+    fn convolve(&self, a: &[u64], b: &[u64], n: usize, square: bool) -> Vec<u64> {
+        let load = |x: &[u64]| {
+            let mut v: Vec<u64> = x.iter().map(|&limb| self.to_mont(limb)).collect();
+            v.resize(n, 0);
+            v
+        };
+        let roots = self.roots(n, false);
+        let mut fa = load(a);
+        self.forward(&mut fa, &roots);
+        if square {
+            for x in fa.iter_mut() {
+                *x = self.mul(*x, *x);
+            }
+        } else {
+            let mut fb = load(b);
+            self.forward(&mut fb, &roots);
+            for (x, &y) in fa.iter_mut().zip(&fb) {
+                *x = self.mul(*x, y);
+            }
+        }
+        self.inverse(&mut fa, &self.roots(n, true));
+
+        // multiplying by 1/n in the normal form leaves the Montgomery form
+        let n_inv = pow_mod(n as u64 % self.p, self.p - 2, self.p);
+        for x in fa.iter_mut() {
+            *x = self.mul(*x, n_inv);
+        }
+        fa
+    }
+}
+
+struct Crt {
+    primes: [Prime; 3],
+    // p1^-1 mod p2, (p1 p2)^-1 mod p3 and p1 mod p3, all times R
+    inv_p1: u64,
+    inv_p12: u64,
+    p1_mod_p3: u64,
+}
+
+fn crt() -> &'static Crt {
+    static CRT: OnceLock<Crt> = OnceLock::new();
+    CRT.get_or_init(|| {
+        let primes = [Prime::new(PRIMES[0]), Prime::new(PRIMES[1]), Prime::new(PRIMES[2])];
+        let (p1, p2, p3) = (primes[0].p, primes[1].p, primes[2].p);
+        let inv_p1 = primes[1].to_mont(pow_mod(p1 % p2, p2 - 2, p2));
+        let p12 = (p1 as u128 * p2 as u128 % p3 as u128) as u64;
+        let inv_p12 = primes[2].to_mont(pow_mod(p12, p3 - 2, p3));
+        let p1_mod_p3 = primes[2].to_mont(p1 % p3);
+        Crt { primes, inv_p1, inv_p12, p1_mod_p3 }
+    })
+}
+
+// r += a * b for a zeroed r of a.len() + b.len() limbs
+pub fn mul_ntt(r: &mut [u64], a: &[u64], b: &[u64], square: bool) {
+    let crt = crt();
+    let n = (a.len() + b.len()).next_power_of_two();
+    let [ref q1, ref q2, ref q3] = crt.primes;
+    let split = par::ENABLED;
+    let (c1, (c2, c3)) = par::join(split, || q1.convolve(a, b, n, square), || {
+        par::join(split, || q2.convolve(a, b, n, square), || q3.convolve(a, b, n, square))
+    });
+
+    // Garner: x = x1 + p1 (y2 + p2 y3), with the residues y2 mod p2 and
+    // y3 mod p3 found one prime at a time
+    let (p1, p2) = (q1.p, q2.p);
+    let p12 = p1 as u128 * p2 as u128;
+    let (p12_lo, p12_hi) = (p12 as u64, (p12 >> 64) as u64);
+    // the running sum of the coefficients above the current limb
+    let mut carry = [0u64; 3];
+    for (i, out) in r.iter_mut().enumerate() {
+        let (x1, x2, x3) = (c1[i], c2[i], c3[i]);
+        let y2 = q2.mul(q2.sub(x2, x1 % p2), crt.inv_p1);
+        let low = x1 as u128 + p1 as u128 * y2 as u128;
+        let low_mod_p3 = q3.add(x1 % q3.p, q3.mul(y2 % q3.p, crt.p1_mod_p3));
+        let y3 = q3.mul(q3.sub(x3, low_mod_p3), crt.inv_p12);
+
+        // x = low + p12 * y3, three limbs wide, added to the carry
+        let t_lo = p12_lo as u128 * y3 as u128;
+        let t_hi = p12_hi as u128 * y3 as u128;
+        let s0 = carry[0] as u128 + (t_lo as u64) as u128 + (low as u64) as u128;
+        let s1 = carry[1] as u128 + (t_lo >> 64) + (t_hi as u64) as u128 + (low >> 64) + (s0 >> 64);
+        let s2 = carry[2] as u128 + (t_hi >> 64) + (s1 >> 64);
+        *out = s0 as u64;
+        carry = [s1 as u64, s2 as u64, (s2 >> 64) as u64];
+    }
+    debug_assert!(carry == [0; 3]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::mul_ntt;
+    use mul;
+
+    use std::time::Instant;
+
+    fn limbs(len: usize, seed: u64) -> Vec<u64> {
+        let mut x = seed;
+        (0..len).map(|_| {
+            x ^= x << 13;
+            x ^= x >> 7;
+            x ^= x << 17;
+            x
+        }).collect()
+    }
+
+    fn check(a: &[u64], b: &[u64]) {
+        let mut expected = vec![0; a.len() + b.len()];
+        mul::schoolbook_into(&mut expected, a, b);
+        let mut r = vec![0; a.len() + b.len()];
+        mul_ntt(&mut r, a, b, false);
+        assert!(r == expected, "{} x {} limbs", a.len(), b.len());
+        if a == b {
+            let mut r = vec![0; 2 * a.len()];
+            mul_ntt(&mut r, a, a, true);
+            assert!(r == expected, "{} limbs squared", a.len());
+        }
+    }
+
+    #[test]
+    fn matches_schoolbook() {
+        for &(la, lb) in &[(1, 1), (1, 2), (2, 1), (3, 3), (3, 5), (7, 1), (17, 9), (31, 33), (255, 129), (1001, 999)] {
+            check(&limbs(la, la as u64 + 1), &limbs(lb, lb as u64 + 100));
+            let a = limbs(la, 7);
+            check(&a, &a);
+        }
+    }
+
+    // every coefficient of the convolution is as big as it gets
+    #[test]
+    fn all_ones_limbs() {
+        for &len in &[1, 2, 3, 5, 64, 127, 1000] {
+            let a = vec![u64::MAX; len];
+            check(&a, &a);
+            check(&a, &[u64::MAX]);
+            check(&[u64::MAX; 3], &a);
+        }
+    }
+
+    // prints the times NTT_THRESHOLD and NTT_SQR_THRESHOLD were read off;
+    // cargo test --release --lib -- --ignored --nocapture ntt
+    #[test]
+    #[ignore]
+    fn thresholds() {
+        let best = |f: &mut dyn FnMut()| {
+            (0..3).map(|_| {
+                let start = Instant::now();
+                f();
+                start.elapsed()
+            }).min().unwrap()
+        };
+        for &len in &[10000, 20000, 25000, 30000, 40000, 50000, 60000, 80000] {
+            let (a, b) = (limbs(len, 1), limbs(len, 2));
+            let mut r = vec![0; 2 * len];
+            let toom = best(&mut || { r.iter_mut().for_each(|x| *x = 0); mul::toom3(&mut r, &a, &b, false) });
+            let ntt = best(&mut || { r.iter_mut().for_each(|x| *x = 0); mul_ntt(&mut r, &a, &b, false) });
+            let toom_sqr = best(&mut || { r.iter_mut().for_each(|x| *x = 0); mul::toom3(&mut r, &a, &a, true) });
+            let ntt_sqr = best(&mut || { r.iter_mut().for_each(|x| *x = 0); mul_ntt(&mut r, &a, &a, true) });
+            println!("{:>6} limbs: toom3 {:>10.2?}  ntt {:>10.2?}  toom3 sqr {:>10.2?}  ntt sqr {:>10.2?}",
+                     len, toom, ntt, toom_sqr, ntt_sqr);
+        }
+    }
+}
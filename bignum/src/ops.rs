@@ -0,0 +1,904 @@
+// std trait implementations: comparison, hashing and the arithmetic
+// operators. Every binary operator is implemented once for references,
+// the owned forms forward to it.
+
+use std::cmp::Ordering;
+use std::hash::{Hash,Hasher};
+use std::ops::{Add,Sub,Mul,Div,Rem,Shl,Shr,Neg,Not,BitAnd,BitOr,BitXor};
+use std::ops::{AddAssign,SubAssign,MulAssign,DivAssign,RemAssign,ShlAssign,ShrAssign};
+use std::ops::{BitAndAssign,BitOrAssign,BitXorAssign};
+
+use super::{BigUint,BigInt,IntSign,BigRational,BigFloat,Choice,UInt};
+use bits;
+use limbs;
+
+impl PartialEq for BigUint {
+    fn eq(&self, other: &BigUint) -> bool {
+        limbs::cmp(&self.limbs, &other.limbs) == Ordering::Equal
+    }
+}
+
+impl Eq for BigUint {}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &BigUint) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &BigUint) -> Ordering {
+        limbs::cmp(&self.limbs, &other.limbs)
+    }
+}
+
+impl Hash for BigUint {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.limbs[..limbs::trimmed_len(&self.limbs)].hash(state);
+    }
+}
+
+impl PartialEq for BigInt {
+    fn eq(&self, other: &BigInt) -> bool {
+        self.is_negative() == other.is_negative() && self.num == other.num
+    }
+}
+
+impl Eq for BigInt {}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &BigInt) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &BigInt) -> Ordering {
+        match (self.is_negative(), other.is_negative()) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => self.num.cmp(&other.num),
+            (true, true) => other.num.cmp(&self.num),
+        }
+    }
+}
+
+impl Hash for BigInt {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.is_negative().hash(state);
+        self.num.hash(state);
+    }
+}
+
+impl Neg for BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        let sign = match self.sign {
+            IntSign::Pos => IntSign::Neg,
+            IntSign::Neg => IntSign::Pos,
+        };
+        BigInt::from_biguint(sign, self.num)
+    }
+}
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        -self.clone()
+    }
+}
+
+// forwards the owned forms of a binary operator and its assign form
+// to the implementation for references
+macro_rules! forward_binop {
+    ($t:ty, $imp:ident, $method:ident) => {
+        impl $imp<$t> for $t {
+            type Output = $t;
+
+            fn $method(self, other: $t) -> $t {
+                $imp::$method(&self, &other)
+            }
+        }
+
+        impl<'a> $imp<&'a $t> for $t {
+            type Output = $t;
+
+            fn $method(self, other: &$t) -> $t {
+                $imp::$method(&self, other)
+            }
+        }
+
+        impl<'a> $imp<$t> for &'a $t {
+            type Output = $t;
+
+            fn $method(self, other: $t) -> $t {
+                $imp::$method(self, &other)
+            }
+        }
+    }
+}
+
+// compound assignment through the by-reference operator
+macro_rules! forward_assign {
+    ($t:ty, $imp:ident, $method:ident, $imp_assign:ident, $method_assign:ident) => {
+        impl $imp_assign<$t> for $t {
+            fn $method_assign(&mut self, other: $t) {
+                *self = $imp::$method(&*self, &other);
+            }
+        }
+
+        impl<'a> $imp_assign<&'a $t> for $t {
+            fn $method_assign(&mut self, other: &$t) {
+                *self = $imp::$method(&*self, other);
+            }
+        }
+    }
+}
+
+// the same for operators with a primitive right-hand side
+macro_rules! forward_scalar_binop {
+    ($t:ty, $s:ty, $imp:ident, $method:ident, $imp_assign:ident, $method_assign:ident) => {
+        impl $imp<$s> for $t {
+            type Output = $t;
+
+            fn $method(self, other: $s) -> $t {
+                $imp::$method(&self, other)
+            }
+        }
+
+        impl $imp_assign<$s> for $t {
+            fn $method_assign(&mut self, other: $s) {
+                *self = $imp::$method(&*self, other);
+            }
+        }
+    }
+}
+
+// and for commutative operators with a primitive on the left
+macro_rules! commute_scalar_binop {
+    ($t:ty, $s:ty, $imp:ident, $method:ident) => {
+        impl $imp<$t> for $s {
+            type Output = $t;
+
+            fn $method(self, other: $t) -> $t {
+                $imp::$method(&other, self)
+            }
+        }
+
+        impl<'a> $imp<&'a $t> for $s {
+            type Output = $t;
+
+            fn $method(self, other: &$t) -> $t {
+                $imp::$method(other, self)
+            }
+        }
+    }
+}
+
+impl Add<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn add(self, other: &BigUint) -> BigUint {
+        BigUint::add(self, other)
+    }
+}
+
+impl Sub<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn sub(self, other: &BigUint) -> BigUint {
+        if limbs::cmp(&self.limbs, &other.limbs) == Ordering::Less {
+            panic!("attempt to subtract with overflow");
+        }
+        BigUint::from_limbs(limbs::sub_vec(&self.limbs, &other.limbs))
+    }
+}
+
+impl Mul<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn mul(self, other: &BigUint) -> BigUint {
+        BigUint::mul(self, other)
+    }
+}
+
+impl Div<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn div(self, other: &BigUint) -> BigUint {
+        self.divmod(other).0
+    }
+}
+
+impl Rem<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn rem(self, other: &BigUint) -> BigUint {
+        self.divmod(other).1
+    }
+}
+
+forward_binop!(BigUint, Add, add);
+forward_binop!(BigUint, Sub, sub);
+forward_binop!(BigUint, Mul, mul);
+forward_binop!(BigUint, Div, div);
+forward_binop!(BigUint, Rem, rem);
+forward_assign!(BigUint, Mul, mul, MulAssign, mul_assign);
+forward_assign!(BigUint, Div, div, DivAssign, div_assign);
+forward_assign!(BigUint, Rem, rem, RemAssign, rem_assign);
+
+// addition and subtraction can work in the left operand's buffer
+impl AddAssign<&BigUint> for BigUint {
+    fn add_assign(&mut self, other: &BigUint) {
+        BigUint::add_assign(self, other);
+    }
+}
+
+impl AddAssign<BigUint> for BigUint {
+    fn add_assign(&mut self, other: BigUint) {
+        BigUint::add_assign(self, &other);
+    }
+}
+
+impl SubAssign<&BigUint> for BigUint {
+    fn sub_assign(&mut self, other: &BigUint) {
+        if BigUint::sub_assign(self, other) {
+            panic!("attempt to subtract with overflow");
+        }
+    }
+}
+
+impl SubAssign<BigUint> for BigUint {
+    fn sub_assign(&mut self, other: BigUint) {
+        *self -= &other;
+    }
+}
+
+impl Add<u64> for &BigUint {
+    type Output = BigUint;
+
+    fn add(self, other: u64) -> BigUint {
+        BigUint::add(self, &BigUint::from_u64(other))
+    }
+}
+
+impl Sub<u64> for &BigUint {
+    type Output = BigUint;
+
+    fn sub(self, other: u64) -> BigUint {
+        self - &BigUint::from_u64(other)
+    }
+}
+
+impl Mul<u64> for &BigUint {
+    type Output = BigUint;
+
+    fn mul(self, other: u64) -> BigUint {
+        self.clone() * other
+    }
+}
+
+impl Div<u64> for &BigUint {
+    type Output = BigUint;
+
+    fn div(self, other: u64) -> BigUint {
+        self.divmod_limb(other).0
+    }
+}
+
+impl Rem<u64> for &BigUint {
+    type Output = BigUint;
+
+    fn rem(self, other: u64) -> BigUint {
+        BigUint::from_u64(self.divmod_limb(other).1)
+    }
+}
+
+forward_scalar_binop!(BigUint, u64, Add, add, AddAssign, add_assign);
+forward_scalar_binop!(BigUint, u64, Sub, sub, SubAssign, sub_assign);
+forward_scalar_binop!(BigUint, u64, Div, div, DivAssign, div_assign);
+forward_scalar_binop!(BigUint, u64, Rem, rem, RemAssign, rem_assign);
+commute_scalar_binop!(BigUint, u64, Add, add);
+commute_scalar_binop!(BigUint, u64, Mul, mul);
+
+// multiplying by a limb can reuse the buffer
+impl Mul<u64> for BigUint {
+    type Output = BigUint;
+
+    fn mul(mut self, other: u64) -> BigUint {
+        self *= other;
+        self
+    }
+}
+
+impl MulAssign<u64> for BigUint {
+    fn mul_assign(&mut self, other: u64) {
+        if other == 0 {
+            *self = BigUint::from_u64(0);
+        } else {
+            self.mul_by_limb(other);
+        }
+    }
+}
+
+impl Shl<usize> for &BigUint {
+    type Output = BigUint;
+
+    fn shl(self, n: usize) -> BigUint {
+        self.clone() << n
+    }
+}
+
+impl Shr<usize> for &BigUint {
+    type Output = BigUint;
+
+    fn shr(self, n: usize) -> BigUint {
+        self.clone() >> n
+    }
+}
+
+impl Shl<usize> for BigUint {
+    type Output = BigUint;
+
+    fn shl(mut self, n: usize) -> BigUint {
+        self <<= n;
+        self
+    }
+}
+
+impl Shr<usize> for BigUint {
+    type Output = BigUint;
+
+    fn shr(mut self, n: usize) -> BigUint {
+        self >>= n;
+        self
+    }
+}
+
+impl ShlAssign<usize> for BigUint {
+    fn shl_assign(&mut self, n: usize) {
+        self.bit_shift_left(n);
+    }
+}
+
+impl ShrAssign<usize> for BigUint {
+    fn shr_assign(&mut self, n: usize) {
+        self.bit_shift_right(n);
+    }
+}
+
+impl BitAnd<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn bitand(self, other: &BigUint) -> BigUint {
+        bits::zip_biguint(self, other, |a, b| a & b)
+    }
+}
+
+impl BitOr<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn bitor(self, other: &BigUint) -> BigUint {
+        bits::zip_biguint(self, other, |a, b| a | b)
+    }
+}
+
+impl BitXor<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn bitxor(self, other: &BigUint) -> BigUint {
+        bits::zip_biguint(self, other, |a, b| a ^ b)
+    }
+}
+
+forward_binop!(BigUint, BitAnd, bitand);
+forward_binop!(BigUint, BitOr, bitor);
+forward_binop!(BigUint, BitXor, bitxor);
+forward_assign!(BigUint, BitAnd, bitand, BitAndAssign, bitand_assign);
+forward_assign!(BigUint, BitOr, bitor, BitOrAssign, bitor_assign);
+forward_assign!(BigUint, BitXor, bitxor, BitXorAssign, bitxor_assign);
+
+// the complement of an unsigned number has infinitely many leading
+// ones, so it comes out as the negative BigInt -n - 1
+impl Not for &BigUint {
+    type Output = BigInt;
+
+    fn not(self) -> BigInt {
+        BigInt::from_biguint(IntSign::Neg, self + 1)
+    }
+}
+
+impl Not for BigUint {
+    type Output = BigInt;
+
+    fn not(self) -> BigInt {
+        !&self
+    }
+}
+
+impl Add<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn add(self, other: &BigInt) -> BigInt {
+        BigInt::add(self, other)
+    }
+}
+
+impl Sub<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn sub(self, other: &BigInt) -> BigInt {
+        BigInt::sub(self, other)
+    }
+}
+
+impl Mul<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn mul(self, other: &BigInt) -> BigInt {
+        BigInt::mul(self, other)
+    }
+}
+
+impl Div<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn div(self, other: &BigInt) -> BigInt {
+        self.div_rem(other).0
+    }
+}
+
+impl Rem<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn rem(self, other: &BigInt) -> BigInt {
+        self.div_rem(other).1
+    }
+}
+
+forward_binop!(BigInt, Add, add);
+forward_binop!(BigInt, Sub, sub);
+forward_binop!(BigInt, Mul, mul);
+forward_binop!(BigInt, Div, div);
+forward_binop!(BigInt, Rem, rem);
+forward_assign!(BigInt, Add, add, AddAssign, add_assign);
+forward_assign!(BigInt, Sub, sub, SubAssign, sub_assign);
+forward_assign!(BigInt, Mul, mul, MulAssign, mul_assign);
+forward_assign!(BigInt, Div, div, DivAssign, div_assign);
+forward_assign!(BigInt, Rem, rem, RemAssign, rem_assign);
+
+impl BitAnd<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn bitand(self, other: &BigInt) -> BigInt {
+        bits::zip_bigint(self, other, |a, b| a & b)
+    }
+}
+
+impl BitOr<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn bitor(self, other: &BigInt) -> BigInt {
+        bits::zip_bigint(self, other, |a, b| a | b)
+    }
+}
+
+impl BitXor<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn bitxor(self, other: &BigInt) -> BigInt {
+        bits::zip_bigint(self, other, |a, b| a ^ b)
+    }
+}
+
+forward_binop!(BigInt, BitAnd, bitand);
+forward_binop!(BigInt, BitOr, bitor);
+forward_binop!(BigInt, BitXor, bitxor);
+forward_assign!(BigInt, BitAnd, bitand, BitAndAssign, bitand_assign);
+forward_assign!(BigInt, BitOr, bitor, BitOrAssign, bitor_assign);
+forward_assign!(BigInt, BitXor, bitxor, BitXorAssign, bitxor_assign);
+
+impl Not for &BigInt {
+    type Output = BigInt;
+
+    fn not(self) -> BigInt {
+        bits::not_bigint(self)
+    }
+}
+
+impl Not for BigInt {
+    type Output = BigInt;
+
+    fn not(self) -> BigInt {
+        bits::not_bigint(&self)
+    }
+}
+
+// shifts act on the two's complement, so right shifts round down
+impl Shl<usize> for &BigInt {
+    type Output = BigInt;
+
+    fn shl(self, n: usize) -> BigInt {
+        BigInt::from_biguint(self.sign, &self.num << n)
+    }
+}
+
+impl Shr<usize> for &BigInt {
+    type Output = BigInt;
+
+    fn shr(self, n: usize) -> BigInt {
+        bits::shr_bigint(self, n)
+    }
+}
+
+impl Shl<usize> for BigInt {
+    type Output = BigInt;
+
+    fn shl(self, n: usize) -> BigInt {
+        &self << n
+    }
+}
+
+impl Shr<usize> for BigInt {
+    type Output = BigInt;
+
+    fn shr(self, n: usize) -> BigInt {
+        &self >> n
+    }
+}
+
+impl ShlAssign<usize> for BigInt {
+    fn shl_assign(&mut self, n: usize) {
+        self.num <<= n;
+    }
+}
+
+impl ShrAssign<usize> for BigInt {
+    fn shr_assign(&mut self, n: usize) {
+        *self = bits::shr_bigint(self, n);
+    }
+}
+
+// primitive operands are converted and go through the generic path
+macro_rules! convert_scalar_binop {
+    ($imp:ident, $method:ident, $imp_assign:ident, $method_assign:ident) => {
+        impl<'a> $imp<i64> for &'a BigInt {
+            type Output = BigInt;
+
+            fn $method(self, other: i64) -> BigInt {
+                $imp::$method(self, &BigInt::from_i64(other))
+            }
+        }
+
+        forward_scalar_binop!(BigInt, i64, $imp, $method, $imp_assign, $method_assign);
+    }
+}
+
+convert_scalar_binop!(Add, add, AddAssign, add_assign);
+convert_scalar_binop!(Sub, sub, SubAssign, sub_assign);
+convert_scalar_binop!(Mul, mul, MulAssign, mul_assign);
+convert_scalar_binop!(Div, div, DivAssign, div_assign);
+convert_scalar_binop!(Rem, rem, RemAssign, rem_assign);
+commute_scalar_binop!(BigInt, i64, Add, add);
+commute_scalar_binop!(BigInt, i64, Mul, mul);
+
+impl PartialEq for BigRational {
+    fn eq(&self, other: &BigRational) -> bool {
+        self.numer() == other.numer() && self.denom() == other.denom()
+    }
+}
+
+impl Eq for BigRational {}
+
+impl PartialOrd for BigRational {
+    fn partial_cmp(&self, other: &BigRational) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// the denominators are positive, so cross-multiplying keeps the order
+impl Ord for BigRational {
+    fn cmp(&self, other: &BigRational) -> Ordering {
+        (self.numer() * other.denom()).cmp(&(other.numer() * self.denom()))
+    }
+}
+
+impl Hash for BigRational {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.numer().hash(state);
+        self.denom().hash(state);
+    }
+}
+
+impl Neg for &BigRational {
+    type Output = BigRational;
+
+    fn neg(self) -> BigRational {
+        BigRational::new(-self.numer(), self.denom().clone())
+    }
+}
+
+impl Neg for BigRational {
+    type Output = BigRational;
+
+    fn neg(self) -> BigRational {
+        -&self
+    }
+}
+
+impl Add<&BigRational> for &BigRational {
+    type Output = BigRational;
+
+    fn add(self, other: &BigRational) -> BigRational {
+        BigRational::add(self, other)
+    }
+}
+
+impl Sub<&BigRational> for &BigRational {
+    type Output = BigRational;
+
+    fn sub(self, other: &BigRational) -> BigRational {
+        BigRational::sub(self, other)
+    }
+}
+
+impl Mul<&BigRational> for &BigRational {
+    type Output = BigRational;
+
+    fn mul(self, other: &BigRational) -> BigRational {
+        BigRational::mul(self, other)
+    }
+}
+
+impl Div<&BigRational> for &BigRational {
+    type Output = BigRational;
+
+    fn div(self, other: &BigRational) -> BigRational {
+        BigRational::div(self, other)
+    }
+}
+
+forward_binop!(BigRational, Add, add);
+forward_binop!(BigRational, Sub, sub);
+forward_binop!(BigRational, Mul, mul);
+forward_binop!(BigRational, Div, div);
+forward_assign!(BigRational, Add, add, AddAssign, add_assign);
+forward_assign!(BigRational, Sub, sub, SubAssign, sub_assign);
+forward_assign!(BigRational, Mul, mul, MulAssign, mul_assign);
+forward_assign!(BigRational, Div, div, DivAssign, div_assign);
+
+// equality and ordering are by value; the precision does not take part
+impl PartialEq for BigFloat {
+    fn eq(&self, other: &BigFloat) -> bool {
+        self.mantissa() == other.mantissa() && self.exponent() == other.exponent()
+    }
+}
+
+impl Eq for BigFloat {}
+
+impl PartialOrd for BigFloat {
+    fn partial_cmp(&self, other: &BigFloat) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigFloat {
+    fn cmp(&self, other: &BigFloat) -> Ordering {
+        match (self.is_negative(), other.is_negative()) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => self.cmp_abs(other),
+            (true, true) => other.cmp_abs(self),
+        }
+    }
+}
+
+impl Hash for BigFloat {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.mantissa().hash(state);
+        self.exponent().hash(state);
+    }
+}
+
+impl Neg for &BigFloat {
+    type Output = BigFloat;
+
+    fn neg(self) -> BigFloat {
+        BigFloat::neg(self)
+    }
+}
+
+impl Neg for BigFloat {
+    type Output = BigFloat;
+
+    fn neg(self) -> BigFloat {
+        -&self
+    }
+}
+
+impl Add<&BigFloat> for &BigFloat {
+    type Output = BigFloat;
+
+    fn add(self, other: &BigFloat) -> BigFloat {
+        BigFloat::add(self, other)
+    }
+}
+
+impl Sub<&BigFloat> for &BigFloat {
+    type Output = BigFloat;
+
+    fn sub(self, other: &BigFloat) -> BigFloat {
+        BigFloat::sub(self, other)
+    }
+}
+
+impl Mul<&BigFloat> for &BigFloat {
+    type Output = BigFloat;
+
+    fn mul(self, other: &BigFloat) -> BigFloat {
+        BigFloat::mul(self, other)
+    }
+}
+
+impl Div<&BigFloat> for &BigFloat {
+    type Output = BigFloat;
+
+    fn div(self, other: &BigFloat) -> BigFloat {
+        BigFloat::div(self, other)
+    }
+}
+
+forward_binop!(BigFloat, Add, add);
+forward_binop!(BigFloat, Sub, sub);
+forward_binop!(BigFloat, Mul, mul);
+forward_binop!(BigFloat, Div, div);
+forward_assign!(BigFloat, Add, add, AddAssign, add_assign);
+forward_assign!(BigFloat, Sub, sub, SubAssign, sub_assign);
+forward_assign!(BigFloat, Mul, mul, MulAssign, mul_assign);
+forward_assign!(BigFloat, Div, div, DivAssign, div_assign);
+
+impl Not for Choice {
+    type Output = Choice;
+
+    fn not(self) -> Choice {
+        Choice::from_mask(!self.mask())
+    }
+}
+
+impl BitAnd for Choice {
+    type Output = Choice;
+
+    fn bitand(self, other: Choice) -> Choice {
+        Choice::from_mask(self.mask() & other.mask())
+    }
+}
+
+impl BitOr for Choice {
+    type Output = Choice;
+
+    fn bitor(self, other: Choice) -> Choice {
+        Choice::from_mask(self.mask() | other.mask())
+    }
+}
+
+impl BitXor for Choice {
+    type Output = Choice;
+
+    fn bitxor(self, other: Choice) -> Choice {
+        Choice::from_mask(self.mask() ^ other.mask())
+    }
+}
+
+impl<const LIMBS: usize> PartialOrd for UInt<LIMBS> {
+    fn partial_cmp(&self, other: &UInt<LIMBS>) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<const LIMBS: usize> Ord for UInt<LIMBS> {
+    fn cmp(&self, other: &UInt<LIMBS>) -> Ordering {
+        self.limbs().iter().rev().cmp(other.limbs().iter().rev())
+    }
+}
+
+// UInt is Copy, so its operators take values; unlike the primitive ones
+// they panic on overflow in release builds too, never wrapping
+macro_rules! uint_binop {
+    ($imp:ident, $method:ident, $imp_assign:ident, $method_assign:ident,
+     $checked:ident, $msg:expr) => {
+        impl<const LIMBS: usize> $imp for UInt<LIMBS> {
+            type Output = UInt<LIMBS>;
+
+            fn $method(self, other: UInt<LIMBS>) -> UInt<LIMBS> {
+                match self.$checked(other) {
+                    Ok(r) => r,
+                    Err(_) => panic!($msg),
+                }
+            }
+        }
+
+        impl<const LIMBS: usize> $imp_assign for UInt<LIMBS> {
+            fn $method_assign(&mut self, other: UInt<LIMBS>) {
+                *self = $imp::$method(*self, other);
+            }
+        }
+    }
+}
+
+uint_binop!(Add, add, AddAssign, add_assign, checked_add, "attempt to add with overflow");
+uint_binop!(Sub, sub, SubAssign, sub_assign, checked_sub, "attempt to subtract with overflow");
+uint_binop!(Mul, mul, MulAssign, mul_assign, checked_mul, "attempt to multiply with overflow");
+uint_binop!(Div, div, DivAssign, div_assign, checked_div, "attempt to divide by zero");
+uint_binop!(Rem, rem, RemAssign, rem_assign, checked_rem,
+            "attempt to calculate the remainder with a divisor of zero");
+
+impl<const LIMBS: usize> Shl<usize> for UInt<LIMBS> {
+    type Output = UInt<LIMBS>;
+
+    fn shl(self, n: usize) -> UInt<LIMBS> {
+        assert!(n < UInt::<LIMBS>::BITS, "attempt to shift left with overflow");
+        self.shl_bits(n)
+    }
+}
+
+impl<const LIMBS: usize> Shr<usize> for UInt<LIMBS> {
+    type Output = UInt<LIMBS>;
+
+    fn shr(self, n: usize) -> UInt<LIMBS> {
+        assert!(n < UInt::<LIMBS>::BITS, "attempt to shift right with overflow");
+        self.shr_bits(n)
+    }
+}
+
+impl<const LIMBS: usize> ShlAssign<usize> for UInt<LIMBS> {
+    fn shl_assign(&mut self, n: usize) {
+        *self = *self << n;
+    }
+}
+
+impl<const LIMBS: usize> ShrAssign<usize> for UInt<LIMBS> {
+    fn shr_assign(&mut self, n: usize) {
+        *self = *self >> n;
+    }
+}
+
+impl<const LIMBS: usize> Not for UInt<LIMBS> {
+    type Output = UInt<LIMBS>;
+
+    fn not(self) -> UInt<LIMBS> {
+        let mut limbs = *self.limbs();
+        for x in limbs.iter_mut() {
+            *x = !*x;
+        }
+        UInt::from_limbs(limbs)
+    }
+}
+
+macro_rules! uint_bitop {
+    ($imp:ident, $method:ident, $imp_assign:ident, $method_assign:ident, $op_assign:tt) => {
+        impl<const LIMBS: usize> $imp for UInt<LIMBS> {
+            type Output = UInt<LIMBS>;
+
+            fn $method(mut self, other: UInt<LIMBS>) -> UInt<LIMBS> {
+                $imp_assign::$method_assign(&mut self, other);
+                self
+            }
+        }
+
+        impl<const LIMBS: usize> $imp_assign for UInt<LIMBS> {
+            fn $method_assign(&mut self, other: UInt<LIMBS>) {
+                let mut limbs = *self.limbs();
+                for (x, &y) in limbs.iter_mut().zip(other.limbs()) {
+                    *x $op_assign y;
+                }
+                *self = UInt::from_limbs(limbs);
+            }
+        }
+    }
+}
+
+uint_bitop!(BitAnd, bitand, BitAndAssign, bitand_assign, &=);
+uint_bitop!(BitOr, bitor, BitOrAssign, bitor_assign, |=);
+uint_bitop!(BitXor, bitxor, BitXorAssign, bitxor_assign, ^=);
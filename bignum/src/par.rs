@@ -0,0 +1,256 @@
+// Fork-join parallelism for the divide-and-conquer algorithms. With the
+// `parallel` feature, join queues its second half for a persistent pool
+// of worker threads while the caller runs the first, as long as the
+// thread budget has one to spare; otherwise, and without the feature,
+// both halves run in order on the calling thread. Either way the
+// arithmetic is the same, so results are bit-identical to the serial
+// build.
+//
+// The workers are started on demand, never more than max_threads - 1 of
+// them, and then kept: spawning a thread per split costs about a
+// fifteenth of a product at PAR_THRESHOLD (see near_threshold in
+// benches/parallel.rs), while handing a job to an idle worker costs only
+// a wake-up. The budget below caps the threads busy at once at
+// max_threads, however deep the recursion.
+
+// operands below this many limbs are not worth a thread
+pub const PAR_THRESHOLD: usize = 1024;
+
+// whether splitting work can pay off at all; the serial build keeps its
+// own algorithms instead of the shapes that suit threads
+pub const ENABLED: bool = cfg!(feature = "parallel");
+
+#[cfg(feature = "parallel")]
+mod pool {
+    use std::collections::VecDeque;
+    use std::mem;
+    use std::panic::{self,AssertUnwindSafe};
+    use std::sync::atomic::{AtomicUsize,Ordering};
+    use std::sync::{Arc,Condvar,Mutex,MutexGuard,OnceLock,PoisonError};
+    use std::thread;
+
+    // 0 means the default, one thread per core
+    static MAX_THREADS: AtomicUsize = AtomicUsize::new(0);
+    // threads working on top of the callers', at most max_threads - 1
+    static BUSY: AtomicUsize = AtomicUsize::new(0);
+
+    pub fn max_threads() -> usize {
+        match MAX_THREADS.load(Ordering::Relaxed) {
+            0 => thread::available_parallelism().map(|n| n.get()).unwrap_or(1),
+            n => n,
+        }
+    }
+
+    pub fn set_max_threads(n: usize) {
+        MAX_THREADS.store(n, Ordering::Relaxed);
+    }
+
+    // gives the thread back even if the work panicked
+    struct Token;
+
+    impl Token {
+        // the new number of busy threads, which the pool has to match
+        fn acquire() -> Option<(Token, usize)> {
+            let extra = max_threads().saturating_sub(1);
+            BUSY.fetch_update(Ordering::AcqRel, Ordering::Acquire, |busy| {
+                if busy < extra { Some(busy + 1) } else { None }
+            }).ok().map(|busy| (Token, busy + 1))
+        }
+    }
+
+    impl Drop for Token {
+        fn drop(&mut self) {
+            BUSY.fetch_sub(1, Ordering::AcqRel);
+        }
+    }
+
+    // the panics are caught inside the jobs, so a poisoned lock still
+    // guards consistent data
+    fn lock<T>(m: &Mutex<T>) -> MutexGuard<'_, T> {
+        m.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    trait Job: Send + Sync {
+        fn run(&self);
+    }
+
+    enum State<F, R> {
+        Queued(F),
+        Running,
+        Done(thread::Result<R>),
+        Taken,
+    }
+
+    // the second half of a join, run by a worker or taken back by the
+    // caller, whichever gets to it first
+    struct Slot<F, R> {
+        state: Mutex<State<F, R>>,
+        done: Condvar,
+    }
+
+    impl<F: FnOnce() -> R + Send, R: Send> Job for Slot<F, R> {
+        fn run(&self) {
+            let f = match mem::replace(&mut *lock(&self.state), State::Running) {
+                State::Queued(f) => f,
+                _ => unreachable!("a job runs once"),
+            };
+            let result = panic::catch_unwind(AssertUnwindSafe(f));
+            *lock(&self.state) = State::Done(result);
+            self.done.notify_one();
+        }
+    }
+
+    impl<F, R> Slot<F, R> {
+        fn wait(&self) -> thread::Result<R> {
+            let mut state = lock(&self.state);
+            loop {
+                match mem::replace(&mut *state, State::Taken) {
+                    State::Done(result) => return result,
+                    other => *state = other,
+                }
+                state = self.done.wait(state).unwrap_or_else(PoisonError::into_inner);
+            }
+        }
+    }
+
+    struct Pool {
+        queue: Mutex<VecDeque<Arc<dyn Job>>>,
+        ready: Condvar,
+        workers: AtomicUsize,
+    }
+
+    fn pool() -> &'static Pool {
+        static POOL: OnceLock<Pool> = OnceLock::new();
+        POOL.get_or_init(|| Pool {
+            queue: Mutex::new(VecDeque::new()),
+            ready: Condvar::new(),
+            workers: AtomicUsize::new(0),
+        })
+    }
+
+    impl Pool {
+        // starts one more worker if fewer than busy are running
+        fn grow_to(&'static self, busy: usize) {
+            let grown = self.workers.fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| {
+                if n < busy { Some(n + 1) } else { None }
+            });
+            if grown.is_ok() {
+                thread::Builder::new()
+                    .name("bignum worker".to_string())
+                    .spawn(move || self.work())
+                    .expect("failed to start a worker thread");
+            }
+        }
+
+        // oldest jobs first, as those are the biggest
+        fn work(&self) {
+            let mut queue = lock(&self.queue);
+            loop {
+                match queue.pop_front() {
+                    Some(job) => {
+                        drop(queue);
+                        job.run();
+                        drop(job);
+                        queue = lock(&self.queue);
+                    }
+                    None => queue = self.ready.wait(queue).unwrap_or_else(PoisonError::into_inner),
+                }
+            }
+        }
+
+        // removes the job at addr if no worker has picked it up yet
+        fn reclaim(&self, addr: *const ()) -> bool {
+            let mut queue = lock(&self.queue);
+            match queue.iter().rposition(|job| Arc::as_ptr(job) as *const () == addr) {
+                Some(i) => { queue.remove(i); true }
+                None => false,
+            }
+        }
+    }
+
+    // SAFETY: the caller has to keep everything the job borrows alive
+    // until the job has run and its result has been taken; after that the
+    // slot holds State::Taken, so a worker dropping the last Arc late
+    // touches nothing borrowed
+    unsafe fn erase<'a>(job: Arc<dyn Job + 'a>) -> Arc<dyn Job> {
+        mem::transmute::<Arc<dyn Job + 'a>, Arc<dyn Job>>(job)
+    }
+
+    pub fn join<A, B, RA, RB>(a: A, b: B) -> (RA, RB)
+        where A: FnOnce() -> RA + Send, B: FnOnce() -> RB + Send, RA: Send, RB: Send
+    {
+        let (token, busy) = match Token::acquire() {
+            None => return (a(), b()),
+            Some(acquired) => acquired,
+        };
+        let pool = pool();
+        pool.grow_to(busy);
+        let slot = Arc::new(Slot { state: Mutex::new(State::Queued(b)), done: Condvar::new() });
+        let addr = Arc::as_ptr(&slot) as *const ();
+        // SAFETY: a's panic is caught, so this function cannot return or
+        // unwind before slot.wait() below has taken b's result
+        let job = unsafe { erase(slot.clone()) };
+        lock(&pool.queue).push_back(job);
+        pool.ready.notify_one();
+
+        let ra = panic::catch_unwind(AssertUnwindSafe(a));
+        // nobody picked b up while a ran, so run it here
+        if pool.reclaim(addr) {
+            slot.run();
+        }
+        let rb = slot.wait();
+        drop(token);
+        match (ra, rb) {
+            (Ok(ra), Ok(rb)) => (ra, rb),
+            (Err(e), _) | (_, Err(e)) => panic::resume_unwind(e),
+        }
+    }
+}
+
+#[cfg(feature = "parallel")]
+pub use self::pool::{max_threads,set_max_threads};
+
+// (a(), b()), in parallel when split is set and a thread is free
+#[cfg(feature = "parallel")]
+pub fn join<A, B, RA, RB>(split: bool, a: A, b: B) -> (RA, RB)
+    where A: FnOnce() -> RA + Send, B: FnOnce() -> RB + Send, RA: Send, RB: Send
+{
+    if split { pool::join(a, b) } else { (a(), b()) }
+}
+
+#[cfg(not(feature = "parallel"))]
+pub fn join<A, B, RA, RB>(_split: bool, a: A, b: B) -> (RA, RB)
+    where A: FnOnce() -> RA + Send, B: FnOnce() -> RB + Send, RA: Send, RB: Send
+{
+    (a(), b())
+}
+
+#[cfg(all(test, feature = "parallel"))]
+mod tests {
+    use std::collections::HashSet;
+    use std::panic;
+    use std::thread;
+
+    // the second halves run on the caller or on a few kept workers, not
+    // on a new thread each time
+    #[test]
+    fn workers_are_reused() {
+        super::set_max_threads(3);
+        let mut seen = HashSet::new();
+        for i in 0..200u64 {
+            let (a, (b, id)) = super::join(true, || i * 2, || (i * 3, thread::current().id()));
+            assert_eq!((a, b), (i * 2, i * 3));
+            seen.insert(id);
+        }
+        assert!(seen.len() <= 3, "{} threads", seen.len());
+    }
+
+    #[test]
+    fn panics_reach_the_caller() {
+        super::set_max_threads(3);
+        let result = panic::catch_unwind(|| super::join(true, || 1, || -> u32 { panic!("in b") }));
+        assert!(result.is_err());
+        // the pool still works afterwards
+        assert_eq!(super::join(true, || super::join(true, || 1, || 2), || 3), ((1, 2), 3));
+    }
+}
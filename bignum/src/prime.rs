@@ -0,0 +1,306 @@
+// Primality testing: trial division by a small-prime table, Miller-Rabin,
+// the strong Lucas test and their combination, Baillie-PSW.
+
+use std::sync::OnceLock;
+
+use bitvec::BitVec;
+
+use super::BigUint;
+
+// the trial division table holds the primes below this
+const SMALL_PRIME_LIMIT: usize = 4096;
+
+// Miller-Rabin with these bases is exact below 3.3 * 10^24
+const WITNESSES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+// the primes below n
+pub fn sieve(n: usize) -> Vec<u64> {
+    let mut composite = BitVec::new();
+    composite.resize(n);
+    let mut primes = Vec::new();
+    for i in 2..n {
+        if composite.get(i) {
+            continue;
+        }
+        primes.push(i as u64);
+        let mut j = i * i;
+        while j < n {
+            composite.set(j, true);
+            j += i;
+        }
+    }
+    primes
+}
+
+pub fn small_primes() -> &'static [u64] {
+    static PRIMES: OnceLock<Vec<u64>> = OnceLock::new();
+    PRIMES.get_or_init(|| sieve(SMALL_PRIME_LIMIT))
+}
+
+enum TrialDivision {
+    Prime,
+    Composite,
+    Unknown,
+}
+
+fn trial_division(n: &BigUint) -> TrialDivision {
+    let primes = small_primes();
+    if n.bits() <= 64 {
+        let x = n.limbs.first().cloned().unwrap_or(0);
+        if x < 2 {
+            return TrialDivision::Composite;
+        }
+        if x < SMALL_PRIME_LIMIT as u64 {
+            return if primes.binary_search(&x).is_ok() { TrialDivision::Prime }
+                   else { TrialDivision::Composite };
+        }
+    }
+
+    // one pass over the number for as many primes as fit in a limb
+    let mut i = 0;
+    while i < primes.len() {
+        let mut product = primes[i];
+        let mut j = i + 1;
+        while j < primes.len() && product.checked_mul(primes[j]).is_some() {
+            product *= primes[j];
+            j += 1;
+        }
+        let rem = n.divmod_limb(product).1;
+        if primes[i..j].iter().any(|&p| rem.is_multiple_of(p)) {
+            return TrialDivision::Composite;
+        }
+        i = j;
+    }
+    TrialDivision::Unknown
+}
+
+// Jacobi symbol (a/n) for odd n
+fn jacobi(a: i64, n: &BigUint) -> i32 {
+    let n_mod_8 = n.limbs[0] % 8;
+    let mut result = 1;
+    let mut a = if a < 0 {
+        if n_mod_8 % 4 == 3 {
+            result = -result;
+        }
+        a.unsigned_abs()
+    } else {
+        a as u64
+    };
+    while a % 2 == 0 {
+        if n_mod_8 == 3 || n_mod_8 == 5 {
+            result = -result;
+        }
+        a /= 2;
+    }
+    if a == 1 {
+        return result;
+    }
+    // reciprocity brings both arguments down to a single limb
+    if a % 4 == 3 && n_mod_8 % 4 == 3 {
+        result = -result;
+    }
+    result * jacobi_u64(n.divmod_limb(a).1, a)
+}
+
+fn jacobi_u64(mut a: u64, mut n: u64) -> i32 {
+    let mut result = 1;
+    a %= n;
+    while a != 0 {
+        while a.is_multiple_of(2) {
+            a /= 2;
+            if n % 8 == 3 || n % 8 == 5 {
+                result = -result;
+            }
+        }
+        ::std::mem::swap(&mut a, &mut n);
+        if a % 4 == 3 && n % 4 == 3 {
+            result = -result;
+        }
+        a %= n;
+    }
+    if n == 1 { result } else { 0 }
+}
+
+// (x / 2) mod n for odd n
+fn half_mod(x: BigUint, n: &BigUint) -> BigUint {
+    let x = x % n;
+    if x.limbs[0] % 2 == 1 { (x + n) >> 1 } else { x >> 1 }
+}
+
+// x mod n for a small signed x
+fn signed_mod(x: i64, n: &BigUint) -> BigUint {
+    let r = BigUint::from_u64(x.unsigned_abs()) % n;
+    if x < 0 && !r.is_zero() { n - &r } else { r }
+}
+
+impl BigUint {
+    // strong probable prime test to the given base, for odd self > 2.
+    // the base is taken mod self; a base that is a multiple of self fails,
+    // and bases that are 1 or -1 mod self pass every odd self
+    pub fn is_strong_probable_prime(&self, base: &BigUint) -> bool {
+        let one = BigUint::from_u64(1);
+        let n_minus_one = self - &one;
+        let s = n_minus_one.trailing_zeros().unwrap_or(0);
+        let d = &n_minus_one >> s;
+
+        let base = base % self;
+        if base.is_zero() {
+            return false;
+        }
+        let mut x = base.mod_pow(&d, self);
+        if x == one || x == n_minus_one {
+            return true;
+        }
+        for _ in 1..s {
+            x = x.square() % self;
+            if x == n_minus_one {
+                return true;
+            }
+            if x == one {
+                return false;
+            }
+        }
+        false
+    }
+
+    // strong Lucas probable prime test with Selfridge's parameters,
+    // for odd self > 2
+    pub fn is_strong_lucas_probable_prime(&self) -> bool {
+        let n = self;
+        let root = n.isqrt();
+        if &root.square() == n {
+            return false;
+        }
+
+        // D from 5, -7, 9, -11, ... with (D/n) = -1
+        let mut d: i64 = 5;
+        loop {
+            match jacobi(d, n) {
+                -1 => break,
+                0 => {
+                    // only prime if n is |D| itself
+                    return n == &BigUint::from_u64(d.unsigned_abs());
+                },
+                _ => d = if d > 0 { -(d + 2) } else { -d + 2 },
+            }
+        }
+        let q = (1 - d) / 4;
+        let d_mod = signed_mod(d, n);
+        let q_mod = signed_mod(q, n);
+
+        // n + 1 = k * 2^s
+        let n_plus_one = n + 1;
+        let s = n_plus_one.trailing_zeros().unwrap_or(0);
+        let k = &n_plus_one >> s;
+
+        // U_k, V_k and Q^k with P = 1, from the top bit of k down
+        let mut u = BigUint::from_u64(1);
+        let mut v = BigUint::from_u64(1);
+        let mut qk = q_mod.clone();
+        for i in (0..k.bits() - 1).rev() {
+            // U_2k = U_k V_k, V_2k = V_k^2 - 2 Q^k
+            u = &(&u * &v) % n;
+            v = &(&v.square() + &(n - &(&(&qk * 2) % n))) % n;
+            qk = qk.square() % n;
+            if k.test_bit(i) {
+                // U_k+1 = (U_k + V_k) / 2, V_k+1 = (D U_k + V_k) / 2
+                let next_u = half_mod(&u + &v, n);
+                let next_v = half_mod(&(&(&d_mod * &u) % n) + &v, n);
+                u = next_u;
+                v = next_v;
+                qk = &(&qk * &q_mod) % n;
+            }
+        }
+
+        if u.is_zero() || v.is_zero() {
+            return true;
+        }
+        for _ in 1..s {
+            v = &(&v.square() + &(n - &(&(&qk * 2) % n))) % n;
+            if v.is_zero() {
+                return true;
+            }
+            qk = qk.square() % n;
+        }
+        false
+    }
+
+    // trial division, then Miller-Rabin to base 2 and `rounds` more bases
+    pub fn is_probable_prime(&self, rounds: usize) -> bool {
+        match trial_division(self) {
+            TrialDivision::Prime => return true,
+            TrialDivision::Composite => return false,
+            TrialDivision::Unknown => (),
+        }
+        if !self.is_strong_probable_prime(&BigUint::from_u64(2)) {
+            return false;
+        }
+
+        // bases in [3, n - 2], from a generator seeded by n itself so the
+        // result is reproducible
+        let mut state = self.limbs[0] ^ 0x9E3779B97F4A7C15;
+        let range = self - 4;
+        for _ in 0..rounds {
+            let mut limbs = Vec::with_capacity(self.limbs.len());
+            for _ in 0..self.limbs.len() {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                limbs.push(state);
+            }
+            let base = BigUint::from_limbs(limbs) % &range + 3;
+            if !self.is_strong_probable_prime(&base) {
+                return false;
+            }
+        }
+        true
+    }
+
+    // deterministic below 2^64 (and well beyond), Baillie-PSW above that
+    pub fn is_prime(&self) -> bool {
+        match trial_division(self) {
+            TrialDivision::Prime => return true,
+            TrialDivision::Composite => return false,
+            TrialDivision::Unknown => (),
+        }
+        if self.bits() <= 64 {
+            return WITNESSES.iter()
+                .all(|&w| self.is_strong_probable_prime(&BigUint::from_u64(w)));
+        }
+        self.is_strong_probable_prime(&BigUint::from_u64(2)) &&
+            self.is_strong_lucas_probable_prime()
+    }
+
+    // the smallest prime greater than self
+    pub fn next_prime(&self) -> BigUint {
+        if self < &BigUint::from_u64(2) {
+            return BigUint::from_u64(2);
+        }
+        let mut candidate = self + 1;
+        if candidate.limbs[0].is_multiple_of(2) && candidate != BigUint::from_u64(2) {
+            candidate += 1;
+        }
+        while !candidate.is_prime() {
+            candidate += 2;
+        }
+        candidate
+    }
+
+    // the largest prime smaller than self, if there is one
+    pub fn prev_prime(&self) -> Option<BigUint> {
+        if self <= &BigUint::from_u64(2) {
+            return None;
+        }
+        if self == &BigUint::from_u64(3) {
+            return Some(BigUint::from_u64(2));
+        }
+        let mut candidate = self - 1;
+        if candidate.limbs[0].is_multiple_of(2) {
+            candidate -= 1;
+        }
+        while !candidate.is_prime() {
+            candidate -= 2;
+        }
+        Some(candidate)
+    }
+}
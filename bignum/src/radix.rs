@@ -0,0 +1,165 @@
+// Radix conversion. Both directions split the number by powers
+// step^(2^k), where step is the biggest power of the base that fits
+// in a limb, so the work is dominated by a few big multiplications.
+
+use div;
+use inplace::Scratch;
+use limbs;
+use mul;
+use par::{self,PAR_THRESHOLD};
+
+// below this many limbs digits are peeled off one limb at a time
+pub const DC_THRESHOLD: usize = 30;
+
+struct Power {
+    limbs: Vec<u64>,
+    reciprocal: Vec<u64>,
+}
+
+// little-endian digits of n in base 2^bits, read straight from the limbs
+pub fn to_digits_pow2(n: &[u64], bits: usize) -> Vec<u8> {
+    let n = &n[..limbs::trimmed_len(n)];
+    let total_bits = n.len() * 64;
+    let mask = (1u64 << bits) - 1;
+    let mut result = Vec::with_capacity(total_bits / bits + 1);
+    let mut pos = 0;
+    while pos < total_bits {
+        let (limb, shift) = (pos / 64, pos % 64);
+        let mut digit = n[limb] >> shift;
+        if shift + bits > 64 && limb + 1 < n.len() {
+            digit |= n[limb + 1] << (64 - shift);
+        }
+        result.push((digit & mask) as u8);
+        pos += bits;
+    }
+
+    let mut len = result.len();
+    while len > 1 && result[len - 1] == 0 {
+        len -= 1;
+    }
+    result.truncate(len);
+    if result.is_empty() {
+        result.push(0);
+    }
+    result
+}
+
+// little-endian digits of n, with leading zeros trimmed
+pub fn to_digits(n: &[u64], base: u64, step: u64, digits_per_limb: usize,
+                 scratch: &mut Scratch) -> Vec<u8> {
+    let n = &n[..limbs::trimmed_len(n)];
+
+    // powers[k] = step^(2^k), up to the first one whose square exceeds n
+    let mut powers = vec![vec![step]];
+    while 2 * (powers[powers.len() - 1].len() - 1) < n.len() {
+        let next = {
+            let last = &powers[powers.len() - 1];
+            let sq = mul::sqr_limbs(last);
+            sq[..limbs::trimmed_len(&sq)].to_vec()
+        };
+        powers.push(next);
+    }
+    let powers: Vec<Power> = powers.into_iter().map(|p| {
+        let reciprocal = if p.len() >= DC_THRESHOLD / 2 { div::reciprocal(&p) }
+                         else { Vec::new() };
+        Power { limbs: p, reciprocal }
+    }).collect();
+
+    let mut result = Vec::new();
+    to_digits_rec(n, powers.len() - 1, &powers, base, digits_per_limb, scratch, &mut result);
+
+    let mut len = result.len();
+    while len > 1 && result[len - 1] == 0 {
+        len -= 1;
+    }
+    result.truncate(len);
+    result
+}
+
+// appends exactly 2^(k+1) * digits_per_limb digits of n < powers[k]^2;
+// every node divides into buffers borrowed from the scratch, so once the
+// first path down has sized them nothing more is allocated for quotients
+// and remainders
+fn to_digits_rec(n: &[u64], k: usize, powers: &[Power], base: u64,
+                 digits_per_limb: usize, scratch: &mut Scratch, out: &mut Vec<u8>) {
+    let n = &n[..limbs::trimmed_len(n)];
+    if k == 0 || n.len() < DC_THRESHOLD {
+        let rest = &mut scratch.buf;
+        rest.clear();
+        rest.extend_from_slice(n);
+        let step = powers[0].limbs[0];
+        for _ in 0..(1 << (k + 1)) {
+            let len = limbs::trimmed_len(rest);
+            rest.truncate(len);
+            let mut limb = div::div_rem_limb(rest, step);
+            for _ in 0..digits_per_limb {
+                out.push((limb % base) as u8);
+                limb /= base;
+            }
+        }
+        return;
+    }
+
+    let power = &powers[k];
+    let (mut quot, mut rem) = (scratch.take(), scratch.take());
+    div::div_rem_barrett_into(n, &power.limbs, &power.reciprocal, &mut quot, &mut rem,
+                              &mut scratch.buf);
+    if par::ENABLED && n.len() >= PAR_THRESHOLD {
+        // the high half gets its own buffers and is appended afterwards
+        let (mut high, mut high_scratch) = (Vec::new(), Scratch::new());
+        par::join(true, || to_digits_rec(&rem, k - 1, powers, base, digits_per_limb, scratch, out),
+                  || to_digits_rec(&quot, k - 1, powers, base, digits_per_limb,
+                                   &mut high_scratch, &mut high));
+        out.extend_from_slice(&high);
+    } else {
+        to_digits_rec(&rem, k - 1, powers, base, digits_per_limb, scratch, out);
+        to_digits_rec(&quot, k - 1, powers, base, digits_per_limb, scratch, out);
+    }
+    scratch.give_back(rem);
+    scratch.give_back(quot);
+}
+
+// limbs of the number with the given big-endian digits
+pub fn from_digits(digits: &[u8], base: u64, step: u64, digits_per_limb: usize) -> Vec<u64> {
+    // one chunk per limb, least significant first
+    let mut chunks: Vec<Vec<u64>> = digits.rchunks(digits_per_limb).map(|chunk| {
+        let value = chunk.iter().fold(0, |acc, &d| acc * base + d as u64);
+        vec![value]
+    }).collect();
+    if chunks.is_empty() {
+        return vec![0];
+    }
+
+    // combine neighbours pairwise: lo + hi * step^(2^k)
+    let mut power = vec![step];
+    while chunks.len() > 1 {
+        chunks = combine_level(&chunks, &power);
+        if chunks.len() > 1 {
+            let sq = mul::sqr_limbs(&power);
+            power = sq[..limbs::trimmed_len(&sq)].to_vec();
+        }
+    }
+    chunks.pop().unwrap()
+}
+
+// one round of from_digits' pairing; the pairs are independent, so big
+// levels are split between threads
+fn combine_level(chunks: &[Vec<u64>], power: &[u64]) -> Vec<Vec<u64>> {
+    if par::ENABLED && chunks.len() >= 4 && chunks.len() * power.len() >= 2 * PAR_THRESHOLD {
+        let mid = chunks.len() / 4 * 2;
+        let (lo, hi) = par::join(true, || combine_level(&chunks[..mid], power),
+                                 || combine_level(&chunks[mid..], power));
+        return lo.into_iter().chain(hi).collect();
+    }
+    chunks.chunks(2).map(|pair| match *pair {
+        [ref lo, ref hi] => {
+            let mut value = mul::mul_limbs(hi, power);
+            value.push(0);
+            limbs::add_assign(&mut value, &lo[..limbs::trimmed_len(lo)]);
+            let len = limbs::trimmed_len(&value);
+            value.truncate(len);
+            value
+        }
+        _ => pair[0].clone(),
+    }).collect()
+}
@@ -0,0 +1,104 @@
+// Random numbers. Anything with a next_u64 can drive the generators;
+// Xoshiro256 is a small seedable one, so randomized tests can be
+// reproduced without an external crate. Neither is suitable for keys
+// that have to stay secret.
+
+use super::{BigUint,BigInt};
+
+pub trait Rng {
+    // 64 uniformly random bits
+    fn next_u64(&mut self) -> u64;
+}
+
+// xoshiro256** by Blackman and Vigna
+#[derive(Clone,Debug)]
+pub struct Xoshiro256 {
+    s: [u64; 4],
+}
+
+impl Xoshiro256 {
+    // the state is filled with SplitMix64, so every seed, zero included,
+    // gives a usable state
+    pub fn new(seed: u64) -> Xoshiro256 {
+        let mut x = seed;
+        let mut next = || {
+            x = x.wrapping_add(0x9E3779B97F4A7C15);
+            let mut z = x;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+            z ^ (z >> 31)
+        };
+        Xoshiro256 { s: [next(), next(), next(), next()] }
+    }
+}
+
+impl Rng for Xoshiro256 {
+    fn next_u64(&mut self) -> u64 {
+        let s = &mut self.s;
+        let result = s[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = s[1] << 17;
+        s[2] ^= s[0];
+        s[3] ^= s[1];
+        s[1] ^= s[2];
+        s[0] ^= s[3];
+        s[2] ^= t;
+        s[3] = s[3].rotate_left(45);
+        result
+    }
+}
+
+impl BigUint {
+    // uniform in [0, 2^n)
+    pub fn random_bits<R: Rng>(n: usize, rng: &mut R) -> BigUint {
+        let len = n.div_ceil(64);
+        let mut limbs: Vec<u64> = (0..len).map(|_| rng.next_u64()).collect();
+        if !n.is_multiple_of(64) {
+            limbs[len - 1] &= (1 << (n % 64)) - 1;
+        }
+        BigUint::from_limbs(limbs)
+    }
+
+    // uniform in [0, bound), by drawing as many bits as bound has until
+    // the number lands below it, which takes two tries at most on average
+    pub fn random_below<R: Rng>(bound: &BigUint, rng: &mut R) -> BigUint {
+        assert!(!bound.is_zero(), "random_below needs a positive bound");
+        let bits = bound.bits();
+        loop {
+            let x = BigUint::random_bits(bits, rng);
+            if &x < bound {
+                return x;
+            }
+        }
+    }
+
+    // uniform in [lo, hi)
+    pub fn random_range<R: Rng>(lo: &BigUint, hi: &BigUint, rng: &mut R) -> BigUint {
+        assert!(lo < hi, "random_range needs lo < hi");
+        lo + &BigUint::random_below(&(hi - lo), rng)
+    }
+
+    // a random prime with exactly `bits` bits
+    pub fn random_prime<R: Rng>(bits: usize, rng: &mut R) -> BigUint {
+        assert!(bits >= 2, "there are no primes below 2");
+        if bits == 2 {
+            return BigUint::from_u64(2 + (rng.next_u64() & 1));
+        }
+        loop {
+            let mut candidate = BigUint::random_bits(bits, rng);
+            candidate.set_bit(bits - 1);
+            candidate.set_bit(0);
+            if candidate.is_prime() {
+                return candidate;
+            }
+        }
+    }
+}
+
+impl BigInt {
+    // uniform in [lo, hi)
+    pub fn random_range<R: Rng>(lo: &BigInt, hi: &BigInt, rng: &mut R) -> BigInt {
+        assert!(lo < hi, "random_range needs lo < hi");
+        let width = hi - lo;
+        lo + &BigInt::from(BigUint::random_below(&width.num, rng))
+    }
+}
@@ -0,0 +1,273 @@
+// Exact rationals. Values are always kept reduced, with a positive
+// denominator, so equal numbers have equal representations.
+
+use std::cmp::max;
+use std::str::FromStr;
+
+use super::{BigUint,BigInt,IntSign,BigNumError,BigFloat,RoundingMode};
+
+#[derive(Clone)]
+pub struct BigRational {
+    num: BigInt,
+    den: BigInt,
+}
+
+fn one() -> BigInt {
+    BigInt::from_i64(1)
+}
+
+impl BigRational {
+    // panics if den is zero
+    pub fn new(num: BigInt, den: BigInt) -> BigRational {
+        if den.is_zero() {
+            panic!("BigRational with a zero denominator");
+        }
+        let g = num.gcd(&den);
+        let (mut num, mut den) = (num.div_rem(&g).0, den.div_rem(&g).0);
+        if den.is_negative() {
+            num = -num;
+            den = -den;
+        }
+        BigRational { num, den }
+    }
+
+    pub fn from_integer(n: BigInt) -> BigRational {
+        BigRational { num: n, den: one() }
+    }
+
+    pub fn zero() -> BigRational {
+        BigRational::from_integer(BigInt::zero())
+    }
+
+    pub fn numer(&self) -> &BigInt {
+        &self.num
+    }
+
+    // always positive
+    pub fn denom(&self) -> &BigInt {
+        &self.den
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.num.is_zero()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.num.is_negative()
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.den == one()
+    }
+
+    pub fn abs(&self) -> BigRational {
+        BigRational { num: self.num.abs(), den: self.den.clone() }
+    }
+
+    // panics for zero
+    pub fn recip(&self) -> BigRational {
+        BigRational::new(self.den.clone(), self.num.clone())
+    }
+
+    pub fn add(&self, other: &BigRational) -> BigRational {
+        BigRational::new(&(&self.num * &other.den) + &(&other.num * &self.den),
+                         &self.den * &other.den)
+    }
+
+    pub fn sub(&self, other: &BigRational) -> BigRational {
+        self.add(&-other)
+    }
+
+    pub fn mul(&self, other: &BigRational) -> BigRational {
+        BigRational::new(&self.num * &other.num, &self.den * &other.den)
+    }
+
+    // panics if other is zero
+    pub fn div(&self, other: &BigRational) -> BigRational {
+        BigRational::new(&self.num * &other.den, &self.den * &other.num)
+    }
+
+    // negative powers of zero panic
+    pub fn pow(&self, exp: i32) -> BigRational {
+        let e = exp.unsigned_abs();
+        let result = BigRational { num: self.num.pow(e), den: self.den.pow(e) };
+        if exp < 0 { result.recip() } else { result }
+    }
+
+    // the largest integer <= self
+    pub fn floor(&self) -> BigInt {
+        self.num.div_mod_floor(&self.den).0
+    }
+
+    // the smallest integer >= self
+    pub fn ceil(&self) -> BigInt {
+        -(-self).floor()
+    }
+
+    // rounds towards zero
+    pub fn trunc(&self) -> BigInt {
+        self.num.div_rem(&self.den).0
+    }
+
+    // the nearest integer, with halves rounded away from zero
+    pub fn round(&self) -> BigInt {
+        let half = BigRational { num: one(), den: BigInt::from_i64(2) };
+        if self.is_negative() {
+            -self.abs().add(&half).floor()
+        } else {
+            self.add(&half).floor()
+        }
+    }
+
+    // the exact value of x; None for NaN and the infinities
+    pub fn from_f64(x: f64) -> Option<BigRational> {
+        if !x.is_finite() {
+            return None;
+        }
+        // x = mantissa * 2^(exponent - 1075), with subnormals
+        // sharing the exponent of the smallest normal numbers
+        let bits = x.to_bits();
+        let biased = ((bits >> 52) & 0x7FF) as i64;
+        let fraction = bits & ((1 << 52) - 1);
+        let (mantissa, exponent) = if biased == 0 { (fraction, -1074) }
+                                   else { (fraction | (1 << 52), biased - 1075) };
+        let sign = if x < 0.0 { IntSign::Neg } else { IntSign::Pos };
+        let mantissa = BigInt::from_biguint(sign, BigUint::from_u64(mantissa));
+        let pow2 = BigUint::from_u64(1) << exponent.unsigned_abs() as usize;
+        let pow2 = BigInt::from_biguint(IntSign::Pos, pow2);
+        if exponent >= 0 {
+            Some(BigRational::from_integer(&mantissa * &pow2))
+        } else {
+            Some(BigRational::new(mantissa, pow2))
+        }
+    }
+
+    // the nearest f64, or an infinity past its range; subnormal results
+    // are rounded twice and can be off by one unit in the last place
+    pub fn to_f64(&self) -> f64 {
+        let exact = |n: &BigInt| BigFloat::from_bigint(n, max(n.num.bits(), 1),
+                                                      RoundingMode::NearestEven);
+        exact(&self.num).div_round(&exact(&self.den), 53, RoundingMode::NearestEven).to_f64()
+    }
+
+    // the value rounded to the given number of decimal places, halves
+    // away from zero
+    pub fn to_decimal_string(&self, places: usize) -> String {
+        let scale = BigInt::from_i64(10).pow(places as u32);
+        let scaled = self.mul(&BigRational::from_integer(scale)).round();
+        let mut digits = scaled.num.to_string();
+        if places > 0 {
+            if digits.len() <= places {
+                digits = "0".repeat(places + 1 - digits.len()) + &digits;
+            }
+            digits.insert(digits.len() - places, '.');
+        }
+        if scaled.is_negative() { format!("-{}", digits) } else { digits }
+    }
+
+    // the exact decimal expansion, with the repeating part in
+    // parentheses: 1/4 is "0.25", 1/6 is "0.1(6)". Expansions that need
+    // more than max_digits fractional digits are cut off with "..."
+    pub fn to_repeating_decimal(&self, max_digits: usize) -> String {
+        let ten = BigUint::from_u64(10);
+        let den = &self.den.num;
+        let (int, mut rem) = self.num.num.divmod(den);
+        let mut result = if self.is_negative() { format!("-{}", int) } else { int.to_string() };
+        if rem.is_zero() {
+            return result;
+        }
+
+        // the digits repeat once the factors of 2 and 5 in the
+        // denominator have been used up
+        let twos = den.trailing_zeros().unwrap_or(0);
+        let mut fives = 0;
+        let mut rest = den.clone();
+        loop {
+            let (q, r) = rest.divmod_limb(5);
+            if r != 0 {
+                break;
+            }
+            rest = q;
+            fives += 1;
+        }
+        let preperiod = twos.max(fives);
+
+        result.push('.');
+        let mut digits = String::new();
+        // the remainder at the start of the repeating part
+        let mut start = None;
+        loop {
+            if rem.is_zero() {
+                return result + &digits;
+            }
+            if digits.len() == preperiod {
+                start = Some(rem.clone());
+            } else if digits.len() > preperiod && start.as_ref() == Some(&rem) {
+                return format!("{}{}({})", result, &digits[..preperiod], &digits[preperiod..]);
+            }
+            if digits.len() == max_digits {
+                return format!("{}{}...", result, digits);
+            }
+            let (digit, next) = (&rem * &ten).divmod(den);
+            digits.push_str(&digit.to_string());
+            rem = next;
+        }
+    }
+}
+
+impl FromStr for BigRational {
+    type Err = BigNumError;
+
+    // accepts "a/b", integers and decimals like "-12.75" or "1.5e-3"
+    fn from_str(s: &str) -> Result<BigRational, BigNumError> {
+        let (negative, s) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        let value = if let Some((num, den)) = s.split_once('/') {
+            let den = parse_digits(den)?;
+            if den.is_zero() {
+                return Err(BigNumError::DivisionByZero);
+            }
+            BigRational::new(BigInt::from(parse_digits(num)?), BigInt::from(den))
+        } else {
+            parse_decimal(s)?
+        };
+        Ok(if negative { -value } else { value })
+    }
+}
+
+// the largest written exponent parse accepts; the value is exact, so
+// 1e1000000000 would need a gigabit of numerator
+const MAX_EXP10: u64 = 100_000;
+
+// "<int>[.<frac>][e<exp>]", unsigned, with at least one digit
+fn parse_decimal(s: &str) -> Result<BigRational, BigNumError> {
+    let (s, exp10) = match s.find(['e', 'E']) {
+        Some(pos) => {
+            let exp = s[pos + 1..].parse::<i64>()
+                .map_err(|_| BigNumError::InvalidExponent)?;
+            if exp.unsigned_abs() > MAX_EXP10 {
+                return Err(BigNumError::InvalidExponent);
+            }
+            (&s[..pos], exp)
+        }
+        None => (s, 0),
+    };
+    let (int, frac) = s.split_once('.').unwrap_or((s, ""));
+    if int.is_empty() && frac.is_empty() {
+        return Err(BigNumError::EmptyString);
+    }
+    let mag = BigInt::from(parse_digits(&format!("{}{}", int, frac))?);
+    let exp10 = exp10 - frac.len() as i64;
+    let pow10 = BigInt::from(BigUint::from_u64(10).pow(exp10.unsigned_abs() as u32));
+    Ok(if exp10 >= 0 { BigRational::from_integer(&mag * &pow10) }
+       else { BigRational::new(mag, pow10) })
+}
+
+fn parse_digits(s: &str) -> Result<BigUint, BigNumError> {
+    if !s.bytes().all(|c| c.is_ascii_digit()) {
+        return Err(BigNumError::InvalidDigit);
+    }
+    BigUint::from_base_string(s, 10)
+}
@@ -0,0 +1,117 @@
+// serde support: decimal strings in human-readable formats, bytes in
+// binary ones. BigUint is little-endian bytes, BigInt the shortest
+// little-endian two's complement, as in to_signed_bytes_le.
+
+use std::fmt;
+
+use serde::{Serialize,Serializer,Deserialize,Deserializer};
+use serde::de::{self,Visitor,SeqAccess};
+
+use super::{BigUint,BigInt,IntSign,BigNumError};
+
+impl Serialize for BigUint {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_str(self)
+        } else {
+            serializer.serialize_bytes(&self.to_bytes_le())
+        }
+    }
+}
+
+impl Serialize for BigInt {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_str(self)
+        } else {
+            serializer.serialize_bytes(&self.to_signed_bytes_le())
+        }
+    }
+}
+
+fn parse_biguint(s: &str) -> Result<BigUint, BigNumError> {
+    if s.starts_with('+') || s.starts_with('-') {
+        return Err(BigNumError::InvalidDigit);
+    }
+    BigUint::from_base_string(s, 10)
+}
+
+fn parse_bigint(s: &str) -> Result<BigInt, BigNumError> {
+    match s.strip_prefix('-') {
+        Some(digits) => Ok(BigInt::from_biguint(IntSign::Neg, parse_biguint(digits)?)),
+        None => Ok(BigInt::from_biguint(IntSign::Pos, parse_biguint(s)?)),
+    }
+}
+
+// both types accept either representation, whatever the format claims
+struct BigUintVisitor;
+
+impl<'de> Visitor<'de> for BigUintVisitor {
+    type Value = BigUint;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a decimal string or little-endian bytes")
+    }
+
+    fn visit_str<E: de::Error>(self, s: &str) -> Result<BigUint, E> {
+        parse_biguint(s).map_err(E::custom)
+    }
+
+    fn visit_bytes<E: de::Error>(self, bytes: &[u8]) -> Result<BigUint, E> {
+        Ok(BigUint::from_bytes_le(bytes))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<BigUint, A::Error> {
+        let mut bytes = Vec::new();
+        while let Some(b) = seq.next_element()? {
+            bytes.push(b);
+        }
+        Ok(BigUint::from_bytes_le(&bytes))
+    }
+}
+
+struct BigIntVisitor;
+
+impl<'de> Visitor<'de> for BigIntVisitor {
+    type Value = BigInt;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a decimal string or little-endian two's complement bytes")
+    }
+
+    fn visit_str<E: de::Error>(self, s: &str) -> Result<BigInt, E> {
+        parse_bigint(s).map_err(E::custom)
+    }
+
+    fn visit_bytes<E: de::Error>(self, bytes: &[u8]) -> Result<BigInt, E> {
+        Ok(BigInt::from_signed_bytes_le(bytes))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<BigInt, A::Error> {
+        let mut bytes = Vec::new();
+        while let Some(b) = seq.next_element()? {
+            bytes.push(b);
+        }
+        Ok(BigInt::from_signed_bytes_le(&bytes))
+    }
+}
+
+impl<'de> Deserialize<'de> for BigUint {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<BigUint, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(BigUintVisitor)
+        } else {
+            deserializer.deserialize_bytes(BigUintVisitor)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for BigInt {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<BigInt, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(BigIntVisitor)
+        } else {
+            deserializer.deserialize_bytes(BigIntVisitor)
+        }
+    }
+}
@@ -0,0 +1,271 @@
+// Fixed-width unsigned integers. UInt<LIMBS> keeps its little-endian
+// limbs in an array, so it is Copy and never allocates, which suits hot
+// loops over 256- or 512-bit values. The overflowing, wrapping and
+// checked forms work like the primitive ones, on the same limb kernels
+// as BigUint; the operators panic on overflow. UInt<0> has no room for
+// a value, and code that builds one from a u64 fails to compile.
+
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+
+use super::{BigUint,BigNumError,long_mul};
+use div;
+use limbs;
+
+#[derive(Clone,Copy,PartialEq,Eq,Hash)]
+pub struct UInt<const LIMBS: usize> {
+    limbs: [u64; LIMBS],
+}
+
+pub type U256 = UInt<4>;
+pub type U512 = UInt<8>;
+
+impl<const LIMBS: usize> UInt<LIMBS> {
+    pub const BITS: usize = 64 * LIMBS;
+    pub const ZERO: UInt<LIMBS> = UInt { limbs: [0; LIMBS] };
+    pub const MAX: UInt<LIMBS> = UInt { limbs: [!0; LIMBS] };
+    const HAS_LIMBS: () = assert!(LIMBS > 0, "UInt needs at least one limb");
+
+    pub const fn from_limbs(limbs: [u64; LIMBS]) -> UInt<LIMBS> {
+        UInt { limbs }
+    }
+
+    pub fn from_u64(n: u64) -> UInt<LIMBS> {
+        let () = UInt::<LIMBS>::HAS_LIMBS;
+        let mut limbs = [0; LIMBS];
+        limbs[0] = n;
+        UInt { limbs }
+    }
+
+    pub fn one() -> UInt<LIMBS> {
+        UInt::from_u64(1)
+    }
+
+    // the little-endian limbs, leading zeros included
+    pub fn limbs(&self) -> &[u64; LIMBS] {
+        &self.limbs
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.iter().all(|&x| x == 0)
+    }
+
+    // number of significant bits
+    pub fn bits(&self) -> usize {
+        let len = limbs::trimmed_len(&self.limbs);
+        if len == 0 {
+            return 0;
+        }
+        64 * len - self.limbs[len - 1].leading_zeros() as usize
+    }
+
+    pub fn leading_zeros(&self) -> u32 {
+        (UInt::<LIMBS>::BITS - self.bits()) as u32
+    }
+
+    pub fn test_bit(&self, pos: usize) -> bool {
+        pos < UInt::<LIMBS>::BITS && self.limbs[pos / 64] >> (pos % 64) & 1 == 1
+    }
+
+    pub fn overflowing_add(self, other: UInt<LIMBS>) -> (UInt<LIMBS>, bool) {
+        let mut r = self;
+        let carry = limbs::add_assign(&mut r.limbs, &other.limbs);
+        (r, carry != 0)
+    }
+
+    pub fn overflowing_sub(self, other: UInt<LIMBS>) -> (UInt<LIMBS>, bool) {
+        let mut r = self;
+        let borrow = limbs::sub_assign(&mut r.limbs, &other.limbs);
+        (r, borrow != 0)
+    }
+
+    // the product modulo 2^BITS; row i only needs the low LIMBS - i limbs
+    // of the other operand, anything above them overflows
+    pub fn overflowing_mul(self, other: UInt<LIMBS>) -> (UInt<LIMBS>, bool) {
+        let mut r = UInt::<LIMBS>::ZERO;
+        let mut overflow = false;
+        for (i, &x) in self.limbs.iter().enumerate() {
+            if x == 0 {
+                continue;
+            }
+            let (low, high) = other.limbs.split_at(LIMBS - i);
+            let carry = limbs::mac_limb(&mut r.limbs[i..], low, x);
+            overflow |= carry != 0 || high.iter().any(|&y| y != 0);
+        }
+        (r, overflow)
+    }
+
+    // the full product as (low, high) halves
+    pub fn widening_mul(self, other: UInt<LIMBS>) -> (UInt<LIMBS>, UInt<LIMBS>) {
+        let (mut lo, mut hi) = (UInt::<LIMBS>::ZERO, UInt::<LIMBS>::ZERO);
+        for (i, &x) in self.limbs.iter().enumerate() {
+            let mut carry = 0;
+            for (j, &y) in other.limbs.iter().enumerate() {
+                let (p_lo, p_hi) = long_mul(x, y);
+                let k = i + j;
+                let limb = if k < LIMBS { &mut lo.limbs[k] } else { &mut hi.limbs[k - LIMBS] };
+                let (s, c1) = limbs::add_carry(*limb, p_lo, 0);
+                let (s, c2) = limbs::add_carry(s, carry, 0);
+                *limb = s;
+                // x * y + limb + carry < 2^128
+                carry = p_hi + c1 + c2;
+            }
+            hi.limbs[i] = carry;
+        }
+        (lo, hi)
+    }
+
+    pub fn wrapping_add(self, other: UInt<LIMBS>) -> UInt<LIMBS> {
+        self.overflowing_add(other).0
+    }
+
+    pub fn wrapping_sub(self, other: UInt<LIMBS>) -> UInt<LIMBS> {
+        self.overflowing_sub(other).0
+    }
+
+    pub fn wrapping_mul(self, other: UInt<LIMBS>) -> UInt<LIMBS> {
+        self.overflowing_mul(other).0
+    }
+
+    pub fn wrapping_neg(self) -> UInt<LIMBS> {
+        let mut r = self;
+        limbs::negate(&mut r.limbs);
+        r
+    }
+
+    pub fn checked_add(self, other: UInt<LIMBS>) -> Result<UInt<LIMBS>, BigNumError> {
+        match self.overflowing_add(other) {
+            (r, false) => Ok(r),
+            (_, true) => Err(BigNumError::OutOfRange),
+        }
+    }
+
+    pub fn checked_sub(self, other: UInt<LIMBS>) -> Result<UInt<LIMBS>, BigNumError> {
+        match self.overflowing_sub(other) {
+            (r, false) => Ok(r),
+            (_, true) => Err(BigNumError::Negative),
+        }
+    }
+
+    pub fn checked_mul(self, other: UInt<LIMBS>) -> Result<UInt<LIMBS>, BigNumError> {
+        match self.overflowing_mul(other) {
+            (r, false) => Ok(r),
+            (_, true) => Err(BigNumError::OutOfRange),
+        }
+    }
+
+    pub fn checked_div_rem(self, divisor: UInt<LIMBS>)
+                           -> Result<(UInt<LIMBS>, UInt<LIMBS>), BigNumError> {
+        let n = limbs::trimmed_len(&divisor.limbs);
+        if n == 0 {
+            return Err(BigNumError::DivisionByZero);
+        }
+        if n == 1 {
+            let mut q = self;
+            let r = div::div_rem_limb(&mut q.limbs, divisor.limbs[0]);
+            return Ok((q, UInt::from_u64(r)));
+        }
+        if limbs::cmp(&self.limbs, &divisor.limbs) == Ordering::Less {
+            return Ok((UInt::<LIMBS>::ZERO, self));
+        }
+        // the kernel needs one limb more than self for the shifted
+        // dividend, which two arrays side by side provide
+        let len = limbs::trimmed_len(&self.limbs);
+        let (mut quot, mut rem) = (UInt::<LIMBS>::ZERO, UInt::<LIMBS>::ZERO);
+        let mut un = [[0u64; LIMBS]; 2];
+        let mut vn = [0u64; LIMBS];
+        div::div_rem_knuth_slices(&self.limbs[..len], &divisor.limbs[..n], &mut quot.limbs,
+                                  un.as_flattened_mut(), &mut vn);
+        rem.limbs[..n].copy_from_slice(&un[0][..n]);
+        Ok((quot, rem))
+    }
+
+    pub fn checked_div(self, divisor: UInt<LIMBS>) -> Result<UInt<LIMBS>, BigNumError> {
+        self.checked_div_rem(divisor).map(|(q, _)| q)
+    }
+
+    pub fn checked_rem(self, divisor: UInt<LIMBS>) -> Result<UInt<LIMBS>, BigNumError> {
+        self.checked_div_rem(divisor).map(|(_, r)| r)
+    }
+
+    // panics on a zero divisor, like the primitive types
+    pub fn div_rem(self, divisor: UInt<LIMBS>) -> (UInt<LIMBS>, UInt<LIMBS>) {
+        match self.checked_div_rem(divisor) {
+            Ok(qr) => qr,
+            Err(_) => panic!("attempt to divide by zero"),
+        }
+    }
+
+    // shifts by BITS or more leave zero
+    pub(crate) fn shl_bits(self, n: usize) -> UInt<LIMBS> {
+        let mut r = UInt::<LIMBS>::ZERO;
+        let (limb_shift, bit_shift) = (n / 64, (n % 64) as u32);
+        for i in limb_shift..LIMBS {
+            let x = self.limbs[i - limb_shift];
+            r.limbs[i] |= x << bit_shift;
+            if bit_shift > 0 && i + 1 < LIMBS {
+                r.limbs[i + 1] |= x >> (64 - bit_shift);
+            }
+        }
+        r
+    }
+
+    pub(crate) fn shr_bits(self, n: usize) -> UInt<LIMBS> {
+        let mut r = UInt::<LIMBS>::ZERO;
+        let (limb_shift, bit_shift) = (n / 64, (n % 64) as u32);
+        for i in limb_shift..LIMBS {
+            let x = self.limbs[i];
+            r.limbs[i - limb_shift] |= x >> bit_shift;
+            if bit_shift > 0 && i > limb_shift {
+                r.limbs[i - limb_shift - 1] |= x << (64 - bit_shift);
+            }
+        }
+        r
+    }
+}
+
+impl<const LIMBS: usize> Default for UInt<LIMBS> {
+    fn default() -> UInt<LIMBS> {
+        UInt::<LIMBS>::ZERO
+    }
+}
+
+impl<const LIMBS: usize> From<u64> for UInt<LIMBS> {
+    fn from(n: u64) -> UInt<LIMBS> {
+        UInt::from_u64(n)
+    }
+}
+
+impl<const LIMBS: usize> From<UInt<LIMBS>> for BigUint {
+    fn from(n: UInt<LIMBS>) -> BigUint {
+        BigUint::from_limbs(n.limbs.to_vec())
+    }
+}
+
+impl<const LIMBS: usize> From<&UInt<LIMBS>> for BigUint {
+    fn from(n: &UInt<LIMBS>) -> BigUint {
+        BigUint::from(*n)
+    }
+}
+
+impl<const LIMBS: usize> TryFrom<&BigUint> for UInt<LIMBS> {
+    type Error = BigNumError;
+
+    fn try_from(n: &BigUint) -> Result<UInt<LIMBS>, BigNumError> {
+        let len = limbs::trimmed_len(n.limbs());
+        if len > LIMBS {
+            return Err(BigNumError::OutOfRange);
+        }
+        let mut r = UInt::<LIMBS>::ZERO;
+        r.limbs[..len].copy_from_slice(&n.limbs()[..len]);
+        Ok(r)
+    }
+}
+
+impl<const LIMBS: usize> TryFrom<BigUint> for UInt<LIMBS> {
+    type Error = BigNumError;
+
+    fn try_from(n: BigUint) -> Result<UInt<LIMBS>, BigNumError> {
+        UInt::try_from(&n)
+    }
+}
@@ -0,0 +1,124 @@
+extern crate bignum;
+use bignum::{BigUint,BigInt,IntSign};
+
+mod common;
+use common::XorShift;
+
+fn ubig(n: u128) -> BigUint {
+    BigUint::from_limbs(vec![n as u64, (n >> 64) as u64])
+}
+
+fn big(n: i128) -> BigInt {
+    let sign = if n < 0 { IntSign::Neg } else { IntSign::Pos };
+    BigInt::from_biguint(sign, ubig(n.unsigned_abs()))
+}
+
+// values of mixed sizes, so that the results still fit after shifting
+fn value(rng: &mut XorShift) -> i128 {
+    let v = (((rng.next() as u128) << 64) | rng.next() as u128) as i128;
+    v >> (rng.next() % 128)
+}
+
+#[test]
+fn biguint_bitwise_matches_u128() {
+    let mut rng = XorShift(0x428A2F98D728AE22);
+    for _ in 0..1000 {
+        let (a, b) = (value(&mut rng) as u128, value(&mut rng) as u128 >> 7);
+        assert_eq!(&ubig(a) & &ubig(b), ubig(a & b));
+        assert_eq!(&ubig(a) | &ubig(b), ubig(a | b));
+        assert_eq!(&ubig(a) ^ &ubig(b), ubig(a ^ b));
+        assert_eq!(ubig(a).count_ones(), a.count_ones() as usize);
+
+        let mut x = ubig(a);
+        x ^= ubig(a);
+        assert!(x.is_zero());
+        x.assert_invariants();
+    }
+}
+
+#[test]
+fn single_bits() {
+    let mut n = BigUint::from_u64(0);
+    n.set_bit(130);
+    n.set_bit(3);
+    assert!(n.test_bit(130) && n.test_bit(3));
+    assert!(!n.test_bit(4) && !n.test_bit(1000));
+    assert_eq!(n.count_ones(), 2);
+    assert_eq!(n.limbs().len(), 3);
+
+    n.clear_bit(130);
+    assert_eq!(n, BigUint::from_u64(8));
+    n.assert_invariants();
+    n.clear_bit(500);
+    n.clear_bit(3);
+    assert!(n.is_zero());
+    n.assert_invariants();
+}
+
+#[test]
+fn complement_of_biguint_is_negative() {
+    assert_eq!(!BigUint::from_u64(0), BigInt::from_i64(-1));
+    assert_eq!(!BigUint::from_u64(41), BigInt::from_i64(-42));
+    assert_eq!(!!BigInt::from_i64(-42), BigInt::from_i64(-42));
+}
+
+#[test]
+fn bigint_bitwise_matches_i128() {
+    let mut rng = XorShift(0x7137449123EF65CD);
+    for _ in 0..2000 {
+        let (a, b) = (value(&mut rng), value(&mut rng));
+        assert_eq!(&big(a) & &big(b), big(a & b), "{} & {}", a, b);
+        assert_eq!(&big(a) | &big(b), big(a | b), "{} | {}", a, b);
+        assert_eq!(&big(a) ^ &big(b), big(a ^ b), "{} ^ {}", a, b);
+        if a != i128::MIN {
+            assert_eq!(!big(a), big(!a));
+        }
+        for r in [&big(a) & &big(b), &big(a) | &big(b), !big(a)].iter() {
+            r.assert_invariants();
+        }
+    }
+}
+
+#[test]
+fn bigint_shifts_match_i128() {
+    let mut rng = XorShift(0xB5C0FBCFEC4D3B2F);
+    for _ in 0..2000 {
+        let a = value(&mut rng);
+        let shift = (rng.next() % 140) as usize;
+        let expected = if shift >= 128 { if a < 0 { -1 } else { 0 } } else { a >> shift };
+        assert_eq!(&big(a) >> shift, big(expected), "{} >> {}", a, shift);
+
+        let room = if a < 0 { (!a).leading_zeros() } else { a.leading_zeros() } as usize;
+        let shift = shift.min(room.saturating_sub(1));
+        let mut x = big(a);
+        x <<= shift;
+        assert_eq!(x, big(a << shift), "{} << {}", a, shift);
+        x >>= shift;
+        assert_eq!(x, big(a));
+    }
+    assert_eq!(BigInt::from_i64(-1) >> 1000, BigInt::from_i64(-1));
+    assert_eq!(BigInt::from_i64(-5) >> 1, BigInt::from_i64(-3));
+}
+
+#[test]
+fn bigint_test_bit_is_twos_complement() {
+    let mut rng = XorShift(0xE9B5DBA58189DBBC);
+    for _ in 0..500 {
+        let a = value(&mut rng);
+        let n = big(a);
+        for pos in [0, 1, 5, 63, 64, 65, 100, 127].iter() {
+            assert_eq!(n.test_bit(*pos), (a >> pos) & 1 == 1, "bit {} of {}", pos, a);
+        }
+        assert_eq!(n.test_bit(1000), a < 0);
+    }
+}
+
+#[test]
+fn masking_negative_numbers() {
+    // the low byte of -2 is 0xFE
+    let mask = BigInt::from_i64(0xFF);
+    assert_eq!(&BigInt::from_i64(-2) & &mask, BigInt::from_i64(0xFE));
+    // clearing the low bits of a negative number rounds it down
+    assert_eq!(BigInt::from_i64(-13) & BigInt::from_i64(!7), BigInt::from_i64(-16));
+    assert!((BigInt::from_i64(-13) & BigInt::zero()).sign() == IntSign::Pos);
+}
@@ -0,0 +1,147 @@
+extern crate bignum;
+use bignum::{BigUint,BigInt,IntSign};
+
+mod common;
+use common::XorShift;
+
+use std::cmp::Ordering;
+
+const ROUNDS: usize = 2000;
+
+// random values of every bit length, so zero and one-limb values
+// come up often
+fn value(rng: &mut XorShift) -> u128 {
+    let v = ((rng.next() as u128) << 64) | rng.next() as u128;
+    let bits = rng.next() % 129;
+    if bits == 128 { v } else { v & ((1u128 << bits) - 1) }
+}
+
+fn big(v: u128) -> BigUint {
+    BigUint::from_limbs(vec![v as u64, (v >> 64) as u64])
+}
+
+// checks the representation against the canonical one for v
+fn check(n: &BigUint, v: u128) {
+    n.assert_invariants();
+    let expected = if v >> 64 > 0 { vec![v as u64, (v >> 64) as u64] }
+                   else { vec![v as u64] };
+    assert_eq!(n.limbs(), &expected[..], "expected {}", v);
+}
+
+fn check_int(n: &BigInt, negative: bool, v: u128) {
+    n.assert_invariants();
+    assert!(n.sign() == if negative { IntSign::Neg } else { IntSign::Pos });
+    check(n.magnitude(), v);
+}
+
+#[test]
+fn construction_is_canonical() {
+    check(&BigUint::from_u64(0), 0);
+    check(&BigUint::from_limbs(vec![]), 0);
+    check(&BigUint::from_limbs(vec![0, 0, 0]), 0);
+    check(&BigUint::from_limbs(vec![5, 0, 0, 0]), 5);
+    check(&BigUint::from_limbs(vec![0, 3, 0]), 3 << 64);
+    check(&BigUint::from_str_radix("0000", 10).unwrap(), 0);
+    check(&BigUint::from_str_radix("00001", 16).unwrap(), 1);
+    assert_eq!(BigUint::from_limbs(vec![9, 0]).into_limbs(), vec![9]);
+}
+
+#[test]
+fn shifts_match_u128() {
+    let mut rng = XorShift(0x243F6A8885A308D3);
+    for _ in 0..ROUNDS {
+        let v = value(&mut rng);
+        let shift = (rng.next() % 140) as usize;
+
+        check(&(big(v) >> shift), v.checked_shr(shift as u32).unwrap_or(0));
+        let mut n = big(v);
+        n.bit_shift_right(shift);
+        check(&n, v.checked_shr(shift as u32).unwrap_or(0));
+
+        // only shift left as far as the result still fits
+        let room = v.leading_zeros() as usize;
+        let shift = shift.min(room);
+        check(&(big(v) << shift), v << shift);
+        let mut n = big(v);
+        n.bit_shift_left(shift);
+        check(&n, v << shift);
+    }
+}
+
+#[test]
+fn limb_shifts_match_u128() {
+    let mut rng = XorShift(0x13198A2E03707344);
+    for _ in 0..ROUNDS {
+        let v = value(&mut rng);
+        for limbs in 0..3 {
+            let mut n = big(v);
+            n.limb_shift_right(limbs);
+            check(&n, v.checked_shr(64 * limbs as u32).unwrap_or(0));
+        }
+        if v >> 64 == 0 {
+            let mut n = big(v);
+            n.limb_shift_left(1);
+            check(&n, v << 64);
+        }
+    }
+    // shifting zero never grows it
+    let mut zero = BigUint::from_u64(0);
+    zero.limb_shift_left(3);
+    zero.bit_shift_left(200);
+    check(&zero, 0);
+}
+
+#[test]
+fn cmp_matches_u128() {
+    let mut rng = XorShift(0xA4093822299F31D0);
+    for _ in 0..ROUNDS {
+        let (a, b) = (value(&mut rng), value(&mut rng));
+        assert_eq!(big(a).cmp(&big(b)), a.cmp(&b), "{} vs {}", a, b);
+        assert_eq!(big(a).cmp(&big(a)), Ordering::Equal);
+        assert_eq!(big(a) == big(b), a == b);
+    }
+}
+
+#[test]
+fn sub_matches_u128() {
+    let mut rng = XorShift(0x082EFA98EC4E6C89);
+    for _ in 0..ROUNDS {
+        let (a, b) = (value(&mut rng), value(&mut rng));
+        let (hi, lo) = if a >= b { (a, b) } else { (b, a) };
+        check(&(big(hi) - big(lo)), hi - lo);
+        check_int(&big(a).signed_sub(&big(b)), a < b, hi - lo);
+
+        let mut n = big(a);
+        assert_eq!(n.sub_assign(&big(b)), a < b);
+        check(&n, hi - lo);
+    }
+}
+
+#[test]
+fn arithmetic_results_are_canonical() {
+    let mut rng = XorShift(0x452821E638D01377);
+    for _ in 0..ROUNDS {
+        let (a, b) = (value(&mut rng), value(&mut rng));
+        let (a64, b64) = (a as u64, b as u64);
+
+        if let Some(sum) = a.checked_add(b) {
+            check(&(big(a) + big(b)), sum);
+            let mut n = big(a);
+            n.add_assign(&big(b));
+            check(&n, sum);
+        }
+        check(&(BigUint::from_u64(a64) * BigUint::from_u64(b64)),
+              a64 as u128 * b64 as u128);
+        check(&(big(a) * BigUint::from_u64(0)), 0);
+        if b != 0 {
+            let (q, r) = big(a).divmod(&big(b));
+            check(&q, a / b);
+            check(&r, a % b);
+        }
+        if b64 != 0 {
+            let (q, r) = big(a).divmod_limb(b64);
+            check(&q, a / b64 as u128);
+            assert_eq!(r as u128, a % b64 as u128);
+        }
+    }
+}
@@ -0,0 +1,112 @@
+extern crate bignum;
+use bignum::BigUint;
+
+mod common;
+use common::XorShift;
+
+fn naive_factorial(n: u64) -> BigUint {
+    let mut f = BigUint::from_u64(1);
+    for i in 2..=n {
+        f *= i;
+    }
+    f
+}
+
+#[test]
+fn factorials_match_the_naive_product() {
+    for n in 0..300 {
+        assert_eq!(BigUint::factorial(n), naive_factorial(n), "{}!", n);
+    }
+    assert_eq!(BigUint::factorial(100).to_string(),
+               "93326215443944152681699238856266700490715968264381621468592963895217599993229915\
+                608941463976156518286253697920827223758251185210916864000000000000000000000000");
+    let n = 5000;
+    assert_eq!(BigUint::factorial(n), naive_factorial(n));
+}
+
+#[test]
+fn binomials_follow_pascals_rule() {
+    let mut row = vec![BigUint::from_u64(1)];
+    for n in 0..200u64 {
+        for (k, expected) in row.iter().enumerate() {
+            assert_eq!(&BigUint::binomial(n, k as u64), expected, "C({}, {})", n, k);
+        }
+        assert_eq!(BigUint::binomial(n, n + 1), BigUint::from_u64(0));
+        let mut next = vec![BigUint::from_u64(1)];
+        next.extend(row.windows(2).map(|w| &w[0] + &w[1]));
+        next.push(BigUint::from_u64(1));
+        row = next;
+    }
+
+    let mut rng = XorShift(0x1F83D9ABFB41BD6B);
+    for _ in 0..20 {
+        let n = rng.next() % 2000;
+        let k = rng.next() % (n + 1);
+        let expected = &naive_factorial(n) / &(&naive_factorial(k) * &naive_factorial(n - k));
+        assert_eq!(BigUint::binomial(n, k), expected, "C({}, {})", n, k);
+    }
+}
+
+#[test]
+fn binomials_of_huge_n_with_small_k() {
+    // these would have to sieve up to n
+    let n = 1u64 << 62;
+    let big = BigUint::from_u64(n);
+    assert_eq!(BigUint::binomial(n, 1), big);
+    assert_eq!(BigUint::binomial(n, n - 1), big);
+    assert_eq!(BigUint::binomial(n, 2), &(&big * &(&big - 1)) >> 1);
+    assert_eq!(BigUint::binomial(u64::MAX, u64::MAX), BigUint::from_u64(1));
+    let m = BigUint::from_u64(u64::MAX);
+    assert_eq!(BigUint::binomial(u64::MAX, 3),
+               &(&(&m * &(&m - 1)) * &(&m - 2)) / &BigUint::from_u64(6));
+
+    // C(n, k) = C(n - 1, k - 1) * n / k
+    let n = 1_000_000_000_000u64;
+    let mut c = BigUint::from_u64(1);
+    for k in 1..100 {
+        c = &(&c * &BigUint::from_u64(n - k + 1)) / &BigUint::from_u64(k);
+        assert_eq!(BigUint::binomial(n, k), c, "C({}, {})", n, k);
+    }
+}
+
+#[test]
+fn fibonacci_and_lucas_match_the_recurrences() {
+    let (mut f, mut f1) = (BigUint::from_u64(0), BigUint::from_u64(1));
+    let (mut l, mut l1) = (BigUint::from_u64(2), BigUint::from_u64(1));
+    for n in 0..1000 {
+        assert_eq!(BigUint::fibonacci(n), f, "F({})", n);
+        assert_eq!(BigUint::lucas(n), l, "L({})", n);
+        let next = &f + &f1;
+        f = std::mem::replace(&mut f1, next);
+        let next = &l + &l1;
+        l = std::mem::replace(&mut l1, next);
+    }
+    assert_eq!(BigUint::fibonacci(93).to_string(), "12200160415121876738");
+}
+
+#[test]
+fn primorials_and_catalan_numbers() {
+    let primorials = [1, 1, 2, 6, 6, 30, 30, 210, 210, 210, 210, 2310, 2310, 30030];
+    for (n, &p) in primorials.iter().enumerate() {
+        assert_eq!(BigUint::primorial(n as u64), BigUint::from_u64(p), "{}#", n);
+    }
+    assert_eq!(BigUint::primorial(100).to_string(),
+               "2305567963945518424753102147331756070");
+
+    let catalan = [1, 1, 2, 5, 14, 42, 132, 429, 1430, 4862, 16796, 58786];
+    for (n, &c) in catalan.iter().enumerate() {
+        assert_eq!(BigUint::catalan(n as u64), BigUint::from_u64(c), "C_{}", n);
+    }
+    // C_{n+1} = C_n * 2(2n + 1) / (n + 2)
+    let mut c = BigUint::from_u64(1);
+    for n in 0..300 {
+        assert_eq!(BigUint::catalan(n), c);
+        c = &(c * (2 * (2 * n + 1))) / (n + 2);
+    }
+}
+
+#[test]
+#[should_panic(expected = "attempt to compute a Catalan number with overflow")]
+fn catalan_index_overflow_panics() {
+    BigUint::catalan(u64::MAX / 2 + 1);
+}
@@ -0,0 +1,34 @@
+// helpers shared by the integration tests
+#![allow(dead_code)]
+
+use bignum::BigUint;
+
+pub mod reference;
+
+// deterministic operands without pulling in a rand dependency
+pub struct XorShift(pub u64);
+
+impl XorShift {
+    pub fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    pub fn biguint(&mut self, len: usize) -> BigUint {
+        let mut limbs: Vec<u64> = (0..len).map(|_| self.next()).collect();
+        if let Some(last) = limbs.last_mut() {
+            if *last == 0 {
+                *last = 1;
+            }
+        }
+        BigUint::from_limbs(limbs)
+    }
+}
+
+impl bignum::Rng for XorShift {
+    fn next_u64(&mut self) -> u64 {
+        self.next()
+    }
+}
@@ -0,0 +1,232 @@
+// A slow reference for the arithmetic core, on 32-bit digits with none
+// of the limb kernels, and the differential check shared by the fuzz
+// target and the regression tests. Kept free of edition-specific syntax
+// so both can include it.
+
+use bignum::{BigUint,IntSign};
+use std::cmp::Ordering;
+
+// the longest operand the fuzz input is cut into, in bytes; the bitwise
+// division is quadratic in it
+pub const MAX_OPERAND_BYTES: usize = 256;
+
+// little-endian base 2^32 digits without leading zeros
+#[derive(Clone,Debug,PartialEq,Eq)]
+pub struct Ref(pub Vec<u32>);
+
+impl Ref {
+    pub fn from_bytes_le(bytes: &[u8]) -> Ref {
+        let digits = bytes.chunks(4).map(|chunk| {
+            chunk.iter().rev().fold(0, |acc, &b| (acc << 8) | b as u32)
+        }).collect();
+        Ref::trimmed(digits)
+    }
+
+    pub fn from_biguint(n: &BigUint) -> Ref {
+        let digits = n.limbs().iter().flat_map(|&limb| {
+            vec![limb as u32, (limb >> 32) as u32]
+        }).collect();
+        Ref::trimmed(digits)
+    }
+
+    fn trimmed(mut digits: Vec<u32>) -> Ref {
+        while digits.last() == Some(&0) {
+            digits.pop();
+        }
+        Ref(digits)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn bits(&self) -> usize {
+        match self.0.last() {
+            None => 0,
+            Some(&top) => 32 * self.0.len() - top.leading_zeros() as usize,
+        }
+    }
+
+    fn bit(&self, i: usize) -> bool {
+        self.0.get(i / 32).is_some_and(|&d| d >> (i % 32) & 1 == 1)
+    }
+
+    pub fn to_u128(&self) -> Option<u128> {
+        if self.0.len() > 4 {
+            return None;
+        }
+        Some(self.0.iter().rev().fold(0, |acc, &d| (acc << 32) | d as u128))
+    }
+
+    pub fn cmp(&self, other: &Ref) -> Ordering {
+        self.0.len().cmp(&other.0.len())
+            .then_with(|| self.0.iter().rev().cmp(other.0.iter().rev()))
+    }
+
+    pub fn add(&self, other: &Ref) -> Ref {
+        let len = self.0.len().max(other.0.len());
+        let mut digits = Vec::with_capacity(len + 1);
+        let mut carry = 0u64;
+        for i in 0..len {
+            let sum = carry + *self.0.get(i).unwrap_or(&0) as u64
+                            + *other.0.get(i).unwrap_or(&0) as u64;
+            digits.push(sum as u32);
+            carry = sum >> 32;
+        }
+        digits.push(carry as u32);
+        Ref::trimmed(digits)
+    }
+
+    // self - other, for self >= other
+    pub fn sub(&self, other: &Ref) -> Ref {
+        let mut digits = Vec::with_capacity(self.0.len());
+        let mut borrow = 0i64;
+        for (i, &d) in self.0.iter().enumerate() {
+            let diff = d as i64 - *other.0.get(i).unwrap_or(&0) as i64 - borrow;
+            digits.push(diff as u32);
+            borrow = if diff < 0 { 1 } else { 0 };
+        }
+        assert!(borrow == 0, "reference subtraction underflow");
+        Ref::trimmed(digits)
+    }
+
+    pub fn mul(&self, other: &Ref) -> Ref {
+        let mut digits = vec![0u32; self.0.len() + other.0.len()];
+        for (i, &x) in self.0.iter().enumerate() {
+            let mut carry = 0u64;
+            for (j, &y) in other.0.iter().enumerate() {
+                let t = x as u64 * y as u64 + digits[i + j] as u64 + carry;
+                digits[i + j] = t as u32;
+                carry = t >> 32;
+            }
+            digits[i + other.0.len()] = carry as u32;
+        }
+        Ref::trimmed(digits)
+    }
+
+    pub fn shl(&self, n: usize) -> Ref {
+        let mut digits = vec![0u32; n / 32];
+        let shift = n % 32;
+        let mut carry = 0u32;
+        for &d in &self.0 {
+            digits.push(d << shift | if shift == 0 { 0 } else { carry >> (32 - shift) });
+            carry = d;
+        }
+        digits.push(if shift == 0 { 0 } else { carry >> (32 - shift) });
+        Ref::trimmed(digits)
+    }
+
+    pub fn shr(&self, n: usize) -> Ref {
+        let (skip, shift) = (n / 32, n % 32);
+        let digits = (skip..self.0.len()).map(|i| {
+            let high = if shift == 0 { 0 } else { self.0.get(i + 1).map_or(0, |&d| d << (32 - shift)) };
+            self.0[i] >> shift | high
+        }).collect();
+        Ref::trimmed(digits)
+    }
+
+    // (quotient, remainder) by shifting and subtracting one bit at a time
+    pub fn divmod(&self, d: &Ref) -> (Ref, Ref) {
+        assert!(!d.is_zero(), "reference division by zero");
+        let mut quot = vec![0u32; self.0.len()];
+        let mut rem = Ref(Vec::new());
+        for i in (0..self.bits()).rev() {
+            rem = rem.shl(1);
+            if self.bit(i) {
+                rem = rem.add(&Ref(vec![1]));
+            }
+            if rem.cmp(d) != Ordering::Less {
+                rem = rem.sub(d);
+                quot[i / 32] |= 1 << (i % 32);
+            }
+        }
+        (Ref::trimmed(quot), rem)
+    }
+}
+
+// a BigUint straight from the bytes, leading zero limbs and all, so
+// from_limbs has to trim them
+fn biguint_from_bytes_le(bytes: &[u8]) -> BigUint {
+    let limbs = bytes.chunks(8).map(|chunk| {
+        chunk.iter().rev().fold(0, |acc, &b| (acc << 8) | b as u64)
+    }).collect();
+    BigUint::from_limbs(limbs)
+}
+
+fn check(name: &str, what: &str, n: &BigUint, expected: &Ref) {
+    let limbs = n.limbs();
+    assert!(!limbs.is_empty() && (limbs.len() == 1 || limbs[limbs.len() - 1] != 0),
+            "{}: {}: non-canonical limbs {:?}", name, what, limbs);
+    assert_eq!(&Ref::from_biguint(n), expected, "{}: {}", name, what);
+}
+
+// runs the operations on operands cut from the fuzz input and compares
+// them with the reference, and with u128 arithmetic where it fits.
+// The input is a 16-bit shift amount, a split byte and the operands'
+// little-endian bytes; anything shorter is ignored.
+pub fn check_arith(data: &[u8]) {
+    check_arith_named("fuzz input", data);
+}
+
+// check_arith, with the failures naming the input, e.g. by its file
+pub fn check_arith_named(name: &str, data: &[u8]) {
+    if data.len() < 3 {
+        return;
+    }
+    let shift = (data[0] as usize | (data[1] as usize) << 8) % 1024;
+    let rest = &data[3..];
+    let split = (data[2] as usize * (rest.len() + 1) / 256).min(rest.len());
+    let (a_bytes, b_bytes) = rest.split_at(split);
+    let (a_bytes, b_bytes) = (&a_bytes[..a_bytes.len().min(MAX_OPERAND_BYTES)],
+                              &b_bytes[..b_bytes.len().min(MAX_OPERAND_BYTES)]);
+
+    let (a, b) = (biguint_from_bytes_le(a_bytes), biguint_from_bytes_le(b_bytes));
+    let (ra, rb) = (Ref::from_bytes_le(a_bytes), Ref::from_bytes_le(b_bytes));
+    check(name, "a", &a, &ra);
+    check(name, "b", &b, &rb);
+
+    assert_eq!(a.cmp(&b), ra.cmp(&rb), "{}: cmp", name);
+    assert_eq!(a.highest_bit(), ra.bits(), "{}: highest_bit", name);
+    check(name, "a + b", &(&a + &b), &ra.add(&rb));
+    check(name, "a * b", &(&a * &b), &ra.mul(&rb));
+    check(name, "a^2", &a.square(), &ra.mul(&ra));
+    check(name, "a << shift", &(&a << shift), &ra.shl(shift));
+    check(name, "a >> shift", &(&a >> shift), &ra.shr(shift));
+    let mut shifted = a.clone();
+    shifted.bit_shift_right(shift);
+    check(name, "bit_shift_right", &shifted, &ra.shr(shift));
+    let mut shifted = a.clone();
+    shifted.bit_shift_left(shift);
+    check(name, "bit_shift_left", &shifted, &ra.shl(shift));
+
+    let (big, small, rbig, rsmall) = if ra.cmp(&rb) == Ordering::Less { (&b, &a, &rb, &ra) }
+                                     else { (&a, &b, &ra, &rb) };
+    check(name, "big - small", &(big - small), &rbig.sub(rsmall));
+    let diff = a.signed_sub(&b);
+    check(name, "a.signed_sub(b)", diff.magnitude(), &rbig.sub(rsmall));
+    assert_eq!(diff.is_zero() || ra.cmp(&rb) == Ordering::Greater,
+               diff.sign() == IntSign::Pos, "{}: sign of a.signed_sub(b)", name);
+
+    if !rb.is_zero() {
+        let (q, r) = a.divmod(&b);
+        let (rq, rr) = ra.divmod(&rb);
+        check(name, "a / b", &q, &rq);
+        check(name, "a % b", &r, &rr);
+    }
+
+    if let (Some(x), Some(y)) = (ra.to_u128(), rb.to_u128()) {
+        let as_u128 = |n: &BigUint| Ref::from_biguint(n).to_u128();
+        if let Some(sum) = x.checked_add(y) {
+            assert_eq!(as_u128(&(&a + &b)), Some(sum), "{}: a + b in u128", name);
+        }
+        if let Some(product) = x.checked_mul(y) {
+            assert_eq!(as_u128(&(&a * &b)), Some(product), "{}: a * b in u128", name);
+        }
+        if let (Some(q), Some(r)) = (x.checked_div(y), x.checked_rem(y)) {
+            assert_eq!(as_u128(&(&a / &b)), Some(q), "{}: a / b in u128", name);
+            assert_eq!(as_u128(&(&a % &b)), Some(r), "{}: a % b in u128", name);
+        }
+        assert_eq!(as_u128(&(&a >> shift)), Some(x.checked_shr(shift as u32).unwrap_or(0)),
+                   "{}: a >> shift in u128", name);
+    }
+}
@@ -0,0 +1,144 @@
+extern crate bignum;
+use bignum::{BigUint,BigInt};
+
+mod common;
+use common::XorShift;
+
+use std::convert::TryFrom;
+
+fn random_u128(rng: &mut XorShift) -> u128 {
+    let v = ((rng.next() as u128) << 64) | rng.next() as u128;
+    v >> (rng.next() % 128)
+}
+
+#[test]
+fn primitive_round_trips() {
+    assert_eq!(u8::try_from(BigUint::from(255u8)), Ok(255));
+    assert_eq!(u16::try_from(BigUint::from(65535u16)), Ok(65535));
+    assert_eq!(u32::try_from(&BigUint::from(7u32)), Ok(7));
+    assert_eq!(u64::try_from(BigUint::from(u64::MAX)), Ok(u64::MAX));
+    assert_eq!(u128::try_from(BigUint::from(u128::MAX)), Ok(u128::MAX));
+    assert_eq!(usize::try_from(BigUint::from(usize::MAX)), Ok(usize::MAX));
+
+    for &n in &[0, 1, -1, i128::MIN, i128::MAX, i64::MIN as i128, 1 << 100] {
+        assert_eq!(i128::try_from(BigInt::from(n)), Ok(n));
+    }
+    assert_eq!(i8::try_from(BigInt::from(-128i8)), Ok(-128));
+    assert_eq!(i64::try_from(&BigInt::from(i64::MIN)), Ok(i64::MIN));
+    assert_eq!(isize::try_from(BigInt::from(-5isize)), Ok(-5));
+    assert_eq!(u64::try_from(BigInt::from(u64::MAX)), Ok(u64::MAX));
+    assert_eq!(BigInt::from(BigUint::from(9u8)), BigInt::from(9));
+    assert_eq!(BigUint::try_from(BigInt::from(9)), Ok(BigUint::from(9u8)));
+}
+
+#[test]
+fn out_of_range_conversions_fail() {
+    assert!(u8::try_from(BigUint::from(256u16)).is_err());
+    assert!(i8::try_from(BigUint::from(128u8)).is_err());
+    assert!(i8::try_from(BigInt::from(-129)).is_err());
+    assert!(u64::try_from(BigUint::from(1u128 << 64)).is_err());
+    assert!(u128::try_from(BigUint::from(u128::MAX) + 1).is_err());
+    assert!(u32::try_from(BigInt::from(-1)).is_err());
+    assert!(u128::try_from(BigInt::from(-1)).is_err());
+    assert!(i128::try_from(BigInt::from(i128::MIN) - 1).is_err());
+    assert!(i128::try_from(BigInt::from(i128::MAX) + 1).is_err());
+    assert!(BigUint::try_from(BigInt::from(-1)).is_err());
+}
+
+#[test]
+fn to_f64_rounds_to_nearest() {
+    let mut rng = XorShift(0x3956C25BF348B538);
+    for _ in 0..2000 {
+        let v = random_u128(&mut rng);
+        // u128 as f64 is correctly rounded
+        assert_eq!(BigUint::from(v).to_f64(), v as f64, "{}", v);
+        assert_eq!(BigInt::from(v as i128 >> 1).to_f64(), (v as i128 >> 1) as f64);
+        assert_eq!((-BigInt::from(v >> 1)).to_f64(), -((v >> 1) as f64));
+    }
+
+    // exact ties go to even, anything above a tie goes up
+    let tie = (BigUint::from(1u8) << 200) + (BigUint::from(1u8) << 147);
+    assert_eq!(tie.to_f64(), 2f64.powi(200));
+    let above = &tie + 1;
+    assert_eq!(above.to_f64(), 2f64.powi(200) + 2f64.powi(148));
+
+    let max = BigUint::from_f64(f64::MAX).unwrap();
+    assert_eq!(max.to_f64(), f64::MAX);
+    assert_eq!((BigUint::from(1u8) << 1024).to_f64(), f64::INFINITY);
+    assert_eq!((-BigInt::from(BigUint::from(1u8) << 2000)).to_f64(), f64::NEG_INFINITY);
+}
+
+#[test]
+fn from_f64_truncates() {
+    assert_eq!(BigUint::from_f64(0.0), Some(BigUint::from(0u8)));
+    assert_eq!(BigUint::from_f64(-0.5), Some(BigUint::from(0u8)));
+    assert_eq!(BigUint::from_f64(2.9), Some(BigUint::from(2u8)));
+    assert_eq!(BigUint::from_f64(-1.0), None);
+    assert_eq!(BigUint::from_f64(f64::NAN), None);
+    assert_eq!(BigUint::from_f64(f64::INFINITY), None);
+    assert_eq!(BigInt::from_f64(-2.9), Some(BigInt::from(-2)));
+    assert_eq!(BigInt::from_f64(f64::NEG_INFINITY), None);
+    assert_eq!(BigUint::from_f64(2f64.powi(100)), Some(BigUint::from(1u128 << 100)));
+
+    let mut rng = XorShift(0x59F111F1B605D019);
+    for _ in 0..2000 {
+        let x = f64::from_bits(rng.next() >> 2);
+        if x.is_finite() {
+            let n = BigUint::from_f64(x).unwrap();
+            assert_eq!(n.to_f64(), x.trunc());
+        }
+    }
+}
+
+#[test]
+fn bytes_round_trip() {
+    assert_eq!(BigUint::from(0u8).to_bytes_be(), vec![0]);
+    assert_eq!(BigUint::from(0x0102u16).to_bytes_be(), vec![1, 2]);
+    assert_eq!(BigUint::from(0x0102u16).to_bytes_le(), vec![2, 1]);
+    assert_eq!(BigUint::from_bytes_be(&[]), BigUint::from(0u8));
+    assert_eq!(BigUint::from_bytes_be(&[0, 0, 1, 0]), BigUint::from(256u16));
+
+    let mut rng = XorShift(0x923F82A4AF194F9B);
+    for _ in 0..200 {
+        let v = random_u128(&mut rng);
+        let n = BigUint::from(v);
+        let be = v.to_be_bytes();
+        let skip = be.iter().take_while(|&&b| b == 0).count().min(15);
+        assert_eq!(n.to_bytes_be(), &be[skip..]);
+        assert_eq!(BigUint::from_bytes_be(&be), n);
+        assert_eq!(BigUint::from_bytes_le(&v.to_le_bytes()), n);
+
+        let big = rng.biguint(5);
+        assert_eq!(BigUint::from_bytes_le(&big.to_bytes_le()), big);
+        assert_eq!(BigUint::from_bytes_be(&big.to_bytes_be()), big);
+    }
+}
+
+#[test]
+fn signed_bytes_are_shortest_twos_complement() {
+    let cases: &[(i128, &[u8])] = &[(0, &[0]), (1, &[1]), (-1, &[0xFF]), (127, &[0x7F]),
+                                    (128, &[0x00, 0x80]), (-128, &[0x80]),
+                                    (-129, &[0xFF, 0x7F]), (255, &[0x00, 0xFF]),
+                                    (-256, &[0xFF, 0x00])];
+    for &(n, be) in cases {
+        assert_eq!(BigInt::from(n).to_signed_bytes_be(), be, "{}", n);
+        assert_eq!(BigInt::from_signed_bytes_be(be), BigInt::from(n));
+        let le: Vec<u8> = be.iter().rev().cloned().collect();
+        assert_eq!(BigInt::from(n).to_signed_bytes_le(), le);
+        assert_eq!(BigInt::from_signed_bytes_le(&le), BigInt::from(n));
+    }
+    assert_eq!(BigInt::from_signed_bytes_be(&[]), BigInt::zero());
+    assert_eq!(BigInt::from_signed_bytes_be(&[0xFF, 0xFF, 0xFE]), BigInt::from(-2));
+
+    let mut rng = XorShift(0xAB1C5ED5DA6D8118);
+    for _ in 0..500 {
+        let v = random_u128(&mut rng) as i128;
+        let v = if rng.next() % 2 == 1 { v } else { !v };
+        let n = BigInt::from(v);
+        let bytes = n.to_signed_bytes_le();
+        // the same as i128's, minus the redundant sign bytes
+        assert_eq!(&v.to_le_bytes()[..bytes.len()], &bytes[..]);
+        assert_eq!(BigInt::from_signed_bytes_le(&bytes), n);
+        assert_eq!(BigInt::from_signed_bytes_le(&v.to_le_bytes()), n);
+    }
+}
@@ -0,0 +1,204 @@
+extern crate bignum;
+use bignum::{BigUint,Choice,CtUint,CtMontgomery,MontgomeryCtx};
+
+mod common;
+use common::XorShift;
+
+use std::hint::black_box;
+use std::time::Instant;
+
+fn random_ct(rng: &mut XorShift, width: usize) -> CtUint {
+    CtUint::from_biguint(&rng.biguint(width), width)
+}
+
+fn random_odd_modulus(rng: &mut XorShift, width: usize) -> BigUint {
+    let mut m = rng.biguint(width);
+    if m.limbs()[0].is_multiple_of(2) {
+        m += 1;
+    }
+    m
+}
+
+#[test]
+fn add_sub_and_compare_match_biguint() {
+    let mut rng = XorShift(0x243F6A8885A308D3);
+    for width in 1..10 {
+        let wrap = BigUint::from_u64(1) << (64 * width);
+        for _ in 0..50 {
+            let (a, b) = (random_ct(&mut rng, width), random_ct(&mut rng, width));
+            // equal high limbs exercise the comparison's tie-breaking
+            let b = if rng.next().is_multiple_of(3) {
+                let mut limbs = a.limbs().to_vec();
+                limbs[0] = b.limbs()[0];
+                CtUint::from_biguint(&BigUint::from_limbs(limbs), width)
+            } else { b };
+            let (x, y) = (a.to_biguint(), b.to_biguint());
+
+            let (sum, carry) = a.overflowing_add(&b);
+            let expected = &x + &y;
+            assert_eq!(carry.to_bool(), expected >= wrap);
+            assert_eq!(sum.to_biguint(), &expected % &wrap);
+
+            let (diff, borrow) = a.overflowing_sub(&b);
+            assert_eq!(borrow.to_bool(), x < y);
+            assert_eq!(diff.to_biguint(), &(&(&x + &wrap) - &y) % &wrap);
+
+            assert_eq!(a.ct_eq(&b).to_bool(), x == y);
+            assert!(a.ct_eq(&a).to_bool());
+            assert_eq!(a.ct_lt(&b).to_bool(), x < y);
+            assert_eq!(a.ct_gt(&b).to_bool(), x > y);
+            assert_eq!(a.ct_is_zero().to_bool(), x.is_zero());
+        }
+    }
+    assert!(CtUint::zero(3).ct_is_zero().to_bool());
+}
+
+#[test]
+fn select_and_swap() {
+    let mut rng = XorShift(0x13198A2E03707344);
+    let (a, b) = (random_ct(&mut rng, 4), random_ct(&mut rng, 4));
+    for &c in &[false, true] {
+        let choice = Choice::from_bool(c);
+        let picked = CtUint::select(&a, &b, choice);
+        assert_eq!(picked.limbs(), if c { b.limbs() } else { a.limbs() });
+
+        let (mut x, mut y) = (a.clone(), b.clone());
+        CtUint::conditional_swap(&mut x, &mut y, choice);
+        assert_eq!((x.limbs(), y.limbs()), if c { (b.limbs(), a.limbs()) } else { (a.limbs(), b.limbs()) });
+
+        let mut z = a.clone();
+        z.conditional_assign(&b, choice);
+        assert_eq!(z.limbs(), picked.limbs());
+    }
+    let (t, f) = (Choice::from_bool(true), Choice::from_bool(false));
+    assert!((t & !f).to_bool() && (t | f).to_bool() && (t ^ f).to_bool() && !(t ^ t).to_bool());
+}
+
+#[test]
+#[should_panic]
+fn mismatched_widths_panic() {
+    CtUint::zero(2).overflowing_add(&CtUint::zero(3));
+}
+
+#[test]
+#[should_panic(expected = "the number does not fit in the target type")]
+fn too_wide_a_value_panics() {
+    CtUint::from_biguint(&(BigUint::from_u64(1) << 64), 1);
+}
+
+#[test]
+fn montgomery_matches_the_variable_time_context() {
+    let mut rng = XorShift(0xA4093822299F31D0);
+    for &width in &[1, 2, 3, 8, 16] {
+        let m = random_odd_modulus(&mut rng, width);
+        let (ct, ctx) = (CtMontgomery::new(&m), MontgomeryCtx::new(&m));
+        for _ in 0..5 {
+            let a = rng.biguint(width).divmod(&m).1;
+            let b = rng.biguint(width).divmod(&m).1;
+            let (ca, cb) = (CtUint::from_biguint(&a, width), CtUint::from_biguint(&b, width));
+            let product = ct.from_montgomery(&ct.mul(&ct.to_montgomery(&ca), &ct.to_montgomery(&cb)));
+            assert_eq!(product.to_biguint(), &a.mul(&b) % &m);
+
+            let exp = rng.biguint(width);
+            let expected = ctx.from_montgomery(&ctx.pow(&ctx.to_montgomery(&a), &exp));
+            let got = ct.mod_pow(&ca, &CtUint::from_biguint(&exp, width));
+            assert_eq!(got.to_biguint(), expected, "{}^{} mod {}", a, exp, m);
+        }
+        // bases at or above the modulus are reduced too
+        let all_ones = &(BigUint::from_u64(1) << (64 * width)) - 1;
+        let one = CtUint::from_biguint(&BigUint::from_u64(1), 1);
+        let big = CtUint::from_biguint(&all_ones, width);
+        assert_eq!(ct.mod_pow(&big, &one).to_biguint(), &all_ones % &m);
+    }
+}
+
+// dudect: time an operation on two classes of secret inputs, a fixed
+// one and random ones, interleaved at random, and apply Welch's t-test
+// to the two timing distributions. |t| above 10 is a certain leak; a
+// constant-time operation stays well below 5. Timing is too noisy on
+// shared machines for the default run, so these are ignored; ci.sh
+// runs them with cargo test --release --test ct -- --ignored
+fn welch_t<T, P, F>(rng: &mut XorShift, samples: usize, reps: usize, mut prepare: P, mut op: F) -> f64
+    where P: FnMut(&mut XorShift, bool) -> T, F: FnMut(&T)
+{
+    let classes: Vec<bool> = (0..samples).map(|_| rng.next().is_multiple_of(2)).collect();
+    let inputs: Vec<T> = classes.iter().map(|&c| prepare(rng, c)).collect();
+    let mut times: Vec<(bool, f64)> = classes.iter().zip(&inputs).map(|(&c, input)| {
+        let start = Instant::now();
+        for _ in 0..reps {
+            op(black_box(input));
+        }
+        (c, start.elapsed().as_nanos() as f64)
+    }).collect();
+
+    // interrupts and preemption only ever add time; drop the slowest
+    // tenth so they do not dominate the variances
+    let mut sorted: Vec<f64> = times.iter().map(|t| t.1).collect();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let cutoff = sorted[sorted.len() * 9 / 10];
+    times.retain(|t| t.1 <= cutoff);
+
+    let stats = |class: bool| {
+        let xs: Vec<f64> = times.iter().filter(|t| t.0 == class).map(|t| t.1).collect();
+        let n = xs.len() as f64;
+        let mean = xs.iter().sum::<f64>() / n;
+        let var = xs.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>() / (n - 1.0);
+        (n, mean, var)
+    };
+    let ((n0, m0, v0), (n1, m1, v1)) = (stats(false), stats(true));
+    (m0 - m1) / (v0 / n0 + v1 / n1).sqrt()
+}
+
+const WIDTH: usize = 16;
+
+#[test]
+#[ignore]
+fn timing_compare_and_add() {
+    let mut rng = XorShift(0x082EFA98EC4E6C89);
+    let fixed = random_ct(&mut rng, WIDTH);
+    let prepare = |rng: &mut XorShift, random: bool| {
+        let a = if random { random_ct(rng, WIDTH) } else { fixed.clone() };
+        (a, fixed.clone())
+    };
+    let t = welch_t(&mut rng, 200_000, 16, prepare, |(a, b)| { black_box(a.ct_eq(b)); });
+    assert!(t.abs() < 10.0, "ct_eq: t = {}", t);
+    let t = welch_t(&mut rng, 200_000, 16, prepare, |(a, b)| { black_box(a.ct_lt(b)); });
+    assert!(t.abs() < 10.0, "ct_lt: t = {}", t);
+    let t = welch_t(&mut rng, 200_000, 16, prepare, |(a, b)| { black_box(a.overflowing_add(b)); });
+    assert!(t.abs() < 10.0, "overflowing_add: t = {}", t);
+    let t = welch_t(&mut rng, 200_000, 16, |rng, random| {
+        (fixed.clone(), random_ct(rng, WIDTH), Choice::from_bool(random))
+    }, |(a, b, c)| { black_box(CtUint::select(a, b, *c)); });
+    assert!(t.abs() < 10.0, "select: t = {}", t);
+}
+
+#[test]
+#[ignore]
+fn timing_mod_pow() {
+    let mut rng = XorShift(0x452821E638D01377);
+    let m = random_odd_modulus(&mut rng, 4);
+    let ct = CtMontgomery::new(&m);
+    let base = CtUint::from_biguint(&rng.biguint(4).divmod(&m).1, 4);
+    // a sparse exponent against dense random ones
+    let sparse = CtUint::from_biguint(&BigUint::from_u64(1), 4);
+    let t = welch_t(&mut rng, 20_000, 1, |rng, random| {
+        if random { random_ct(rng, 4) } else { sparse.clone() }
+    }, |exp| { black_box(ct.mod_pow(&base, exp)); });
+    assert!(t.abs() < 10.0, "mod_pow: t = {}", t);
+}
+
+// the harness itself has to catch a leak: the sliding window skips the
+// multiplications for zero bits
+#[test]
+#[ignore]
+fn timing_detects_variable_time_pow() {
+    let mut rng = XorShift(0xBE5466CF34E90C6C);
+    let m = random_odd_modulus(&mut rng, 4);
+    let ctx = MontgomeryCtx::new(&m);
+    let base = ctx.to_montgomery(&rng.biguint(4).divmod(&m).1);
+    let sparse = BigUint::from_u64(1) << 255;
+    let t = welch_t(&mut rng, 20_000, 1, |rng, random| {
+        if random { rng.biguint(4) | sparse.clone() } else { sparse.clone() }
+    }, |exp| { black_box(ctx.pow(&base, exp)); });
+    assert!(t.abs() > 10.0, "MontgomeryCtx::pow: t = {}", t);
+}
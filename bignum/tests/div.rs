@@ -0,0 +1,75 @@
+extern crate bignum;
+use bignum::BigUint;
+
+mod common;
+use common::XorShift;
+
+fn check_divmod(n: &BigUint, d: &BigUint) {
+    let (q, r) = n.divmod(d);
+    assert!(r.cmp(d) == std::cmp::Ordering::Less);
+    assert_eq!(q.mul(d).add(&r).limbs(), n.limbs());
+}
+
+#[test]
+fn divmod_small_values() {
+    let pairs = [(0u64, 1u64), (1, 1), (7, 2), (100, 7), (!0, 3), (12345, 12346)];
+    for &(n, d) in &pairs {
+        let (q, r) = BigUint::from_u64(n).divmod(&BigUint::from_u64(d));
+        assert_eq!(q.limbs(), vec![n / d]);
+        assert_eq!(r.limbs(), vec![n % d]);
+    }
+}
+
+#[test]
+fn divmod_random() {
+    let mut rng = XorShift(0x9E3779B97F4A7C15);
+    for &(ln, ld) in &[(1, 1), (2, 1), (5, 1), (2, 2), (3, 2), (10, 3), (40, 39),
+                       (40, 40), (64, 17), (200, 100), (333, 2)] {
+        for _ in 0..20 {
+            let n = rng.biguint(ln);
+            let d = rng.biguint(ld);
+            check_divmod(&n, &d);
+        }
+    }
+}
+
+#[test]
+fn divmod_adversarial() {
+    // divisors that make the quotient estimate too big, and add-back cases
+    let ones = BigUint::from_limbs(vec![!0; 8]);
+    let top_bit = BigUint::from_limbs(vec![0, 0, 1 << 63]);
+    let half = BigUint::from_limbs(vec![!0, !0 >> 1]);
+    for d in &[ones.clone(), top_bit.clone(), half.clone(),
+               BigUint::from_limbs(vec![1, 1]),
+               BigUint::from_limbs(vec![0, 1 << 63])] {
+        for n in &[ones.clone(), top_bit.clone(), half.clone(),
+                   ones.mul(&ones), top_bit.mul(&half),
+                   BigUint::from_limbs(vec![0, 0, 0, 1 << 63, !0])] {
+            check_divmod(n, d);
+        }
+    }
+}
+
+#[test]
+fn divmod_exact() {
+    let mut rng = XorShift(0x2545F4914F6CDD1D);
+    for _ in 0..20 {
+        let a = rng.biguint(13);
+        let b = rng.biguint(7);
+        let (q, r) = a.mul(&b).divmod(&b);
+        assert_eq!(q.limbs(), a.limbs());
+        assert!(r.is_zero());
+    }
+}
+
+#[test]
+fn divmod_limb() {
+    let mut rng = XorShift(7);
+    let n = rng.biguint(30);
+    for &d in &[1, 2, 10, 1 << 63, !0] {
+        let (q, r) = n.divmod_limb(d);
+        assert_eq!(q.mul(&BigUint::from_u64(d)).add(&BigUint::from_u64(r)).limbs(),
+                   n.limbs());
+        assert!(r < d);
+    }
+}
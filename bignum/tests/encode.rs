@@ -0,0 +1,88 @@
+extern crate bignum;
+use bignum::{BigUint,BigInt};
+
+mod common;
+use common::XorShift;
+
+use std::io::ErrorKind;
+
+fn encode_uint(n: &BigUint) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    n.write_to(&mut bytes).unwrap();
+    bytes
+}
+
+fn encode_int(n: &BigInt) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    n.write_to(&mut bytes).unwrap();
+    bytes
+}
+
+// version, sign, limb count, then the limbs
+fn raw(sign: u8, limbs: &[u64]) -> Vec<u8> {
+    let mut bytes = vec![1, sign];
+    bytes.extend((limbs.len() as u64).to_le_bytes());
+    bytes.extend(limbs.iter().flat_map(|x| x.to_le_bytes()));
+    bytes
+}
+
+#[test]
+fn layout() {
+    assert_eq!(encode_uint(&BigUint::from_u64(0)), raw(0, &[0]));
+    assert_eq!(encode_uint(&BigUint::from(1u128 << 64)), raw(0, &[0, 1]));
+    assert_eq!(encode_int(&BigInt::from(-5)), raw(1, &[5]));
+    assert_eq!(encode_int(&BigInt::from(5)), encode_uint(&BigUint::from_u64(5)));
+}
+
+#[test]
+fn round_trips() {
+    let mut rng = XorShift(0x5BE0CD19137E2179);
+    let mut stream = Vec::new();
+    let mut expected = Vec::new();
+    for len in 1..40 {
+        let n = rng.biguint(len);
+        let i = if rng.next().is_multiple_of(2) { BigInt::from(n.clone()) } else { -BigInt::from(n.clone()) };
+        n.write_to(&mut stream).unwrap();
+        i.write_to(&mut stream).unwrap();
+        expected.push((n, i));
+    }
+    // the values are self-delimiting, so they can share a stream
+    let mut r = &stream[..];
+    for (n, i) in expected {
+        assert_eq!(BigUint::read_from(&mut r).unwrap(), n);
+        assert_eq!(BigInt::read_from(&mut r).unwrap(), i);
+    }
+    assert!(r.is_empty());
+}
+
+#[test]
+fn non_canonical_input_is_rejected() {
+    let bad = [
+        raw(0, &[]),
+        raw(0, &[1, 0]),
+        raw(0, &[0, 0]),
+        raw(1, &[0]),
+        raw(2, &[1]),
+        { let mut v = raw(0, &[1]); v[0] = 2; v },
+    ];
+    for bytes in &bad {
+        let err = BigInt::read_from(&mut &bytes[..]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData, "{:?}", bytes);
+        assert!(BigUint::read_from(&mut &bytes[..]).is_err());
+    }
+    let negative = raw(1, &[7]);
+    assert_eq!(BigUint::read_from(&mut &negative[..]).unwrap_err().kind(), ErrorKind::InvalidData);
+}
+
+#[test]
+fn truncated_input_fails() {
+    let bytes = encode_uint(&BigUint::from(u128::MAX));
+    for end in 0..bytes.len() {
+        let err = BigUint::read_from(&mut &bytes[..end]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    }
+    // a huge length with no limbs behind it fails without allocating it
+    let mut bytes = raw(0, &[1]);
+    bytes[2..10].copy_from_slice(&u64::MAX.to_le_bytes());
+    assert_eq!(BigUint::read_from(&mut &bytes[..]).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+}
@@ -0,0 +1,99 @@
+extern crate bignum;
+use bignum::{BigUint,BigInt,BigRational,BigFloat,BigNumError,RoundingMode};
+
+use std::convert::TryFrom;
+use std::error::Error;
+
+#[test]
+fn radix_errors_carry_the_range() {
+    let n = BigUint::from_u64(255);
+    assert_eq!(n.to_base_string(65), Err(BigNumError::InvalidRadix { radix: 65, min: 2, max: 64 }));
+    assert_eq!(n.digits(1), Err(BigNumError::InvalidRadix { radix: 1, min: 2, max: 256 }));
+    assert_eq!(n.digits(256), Ok(vec![255]));
+    assert_eq!(BigInt::from(-5).to_base_string(0).unwrap_err(),
+               BigNumError::InvalidRadix { radix: 0, min: 2, max: 64 });
+    assert_eq!(BigUint::from_str_radix("10", 1).unwrap_err(),
+               BigNumError::InvalidRadix { radix: 1, min: 2, max: 64 });
+}
+
+#[test]
+fn parse_errors() {
+    assert_eq!(BigUint::from_str_radix("", 10).unwrap_err(), BigNumError::EmptyString);
+    assert_eq!(BigUint::from_str_radix("12a", 10).unwrap_err(), BigNumError::InvalidDigit);
+    assert_eq!("".parse::<BigRational>().unwrap_err(), BigNumError::EmptyString);
+    assert_eq!("1/0".parse::<BigRational>().unwrap_err(), BigNumError::DivisionByZero);
+    assert_eq!("1.x".parse::<BigRational>().unwrap_err(), BigNumError::InvalidDigit);
+    let parse = |s| BigFloat::parse(s, 53, RoundingMode::NearestEven).map(|_| ());
+    assert_eq!(parse("1e"), Err(BigNumError::InvalidExponent));
+    assert_eq!(parse("-"), Err(BigNumError::EmptyString));
+    assert_eq!(parse("1.5x"), Err(BigNumError::InvalidDigit));
+}
+
+#[test]
+fn conversion_errors() {
+    assert_eq!(u8::try_from(BigUint::from(256u16)), Err(BigNumError::OutOfRange));
+    assert_eq!(u128::try_from(BigUint::from(u128::MAX) + 1), Err(BigNumError::OutOfRange));
+    assert_eq!(i8::try_from(BigInt::from(-129)), Err(BigNumError::OutOfRange));
+    assert_eq!(u32::try_from(BigInt::from(-1)), Err(BigNumError::Negative));
+    assert_eq!(u128::try_from(BigInt::from(-1)), Err(BigNumError::Negative));
+    assert_eq!(BigUint::try_from(BigInt::from(-1)).unwrap_err(), BigNumError::Negative);
+}
+
+#[test]
+fn checked_arithmetic() {
+    let (a, b) = (BigUint::from_u64(100), BigUint::from_u64(7));
+    let zero = BigUint::from_u64(0);
+    assert_eq!(a.checked_sub(&b), Ok(BigUint::from_u64(93)));
+    assert_eq!(a.checked_sub(&a), Ok(zero.clone()));
+    assert_eq!(b.checked_sub(&a), Err(BigNumError::Negative));
+    assert_eq!(a.checked_div(&b), Ok(BigUint::from_u64(14)));
+    assert_eq!(a.checked_rem(&b), Ok(BigUint::from_u64(2)));
+    assert_eq!(a.checked_divmod(&b), Ok((BigUint::from_u64(14), BigUint::from_u64(2))));
+    assert_eq!(a.checked_div(&zero), Err(BigNumError::DivisionByZero));
+    assert_eq!(a.checked_rem(&zero), Err(BigNumError::DivisionByZero));
+    // non-canonical zero is still zero
+    assert_eq!(a.checked_div(&BigUint::from_limbs(vec![0, 0])), Err(BigNumError::DivisionByZero));
+
+    let (x, y) = (BigInt::from(-100), BigInt::from(7));
+    assert_eq!(x.checked_div(&y), Ok(BigInt::from(-14)));
+    assert_eq!(x.checked_rem(&y), Ok(BigInt::from(-2)));
+    assert_eq!(x.checked_div(&BigInt::zero()), Err(BigNumError::DivisionByZero));
+    assert_eq!(x.checked_rem(&BigInt::zero()), Err(BigNumError::DivisionByZero));
+}
+
+#[test]
+#[should_panic(expected = "attempt to divide by zero")]
+fn div_by_zero_panics() {
+    let _ = BigUint::from_u64(1) / BigUint::from_u64(0);
+}
+
+#[test]
+#[should_panic(expected = "attempt to divide by zero")]
+fn rem_by_zero_panics() {
+    let _ = BigUint::from_limbs(vec![1, 2, 3]) % BigUint::from_limbs(vec![0, 0]);
+}
+
+#[test]
+#[should_panic(expected = "attempt to divide by zero")]
+fn div_by_zero_limb_panics() {
+    let _ = BigUint::from_u64(1) / 0;
+}
+
+#[test]
+#[should_panic(expected = "attempt to divide by zero")]
+fn signed_div_by_zero_panics() {
+    let _ = BigInt::from(-3).div_rem(&BigInt::zero());
+}
+
+fn parse_sum(a: &str, b: &str) -> Result<BigUint, Box<dyn Error>> {
+    Ok(BigUint::from_str_radix(a, 10)? + BigUint::from_str_radix(b, 10)?)
+}
+
+#[test]
+fn works_as_a_std_error() {
+    assert_eq!(parse_sum("12", "30").unwrap(), BigUint::from_u64(42));
+    let err = parse_sum("12", "3x").unwrap_err();
+    assert_eq!(err.to_string(), "invalid digit for the given base");
+    assert_eq!(BigNumError::InvalidRadix { radix: 99, min: 2, max: 64 }.to_string(),
+               "base 99 is outside the supported range [2, 64]");
+}
@@ -0,0 +1,222 @@
+extern crate bignum;
+use bignum::{BigInt,BigFloat,BigRational,RoundingMode,BigNumError};
+
+mod common;
+use common::XorShift;
+
+use bignum::RoundingMode::*;
+
+const MODES: [RoundingMode; 5] = [NearestEven, NearestAway, TowardZero, TowardPositive, TowardNegative];
+
+fn float(x: f64, prec: usize) -> BigFloat {
+    BigFloat::from_f64(x, prec, NearestEven).unwrap()
+}
+
+fn parse(s: &str, prec: usize) -> BigFloat {
+    BigFloat::parse(s, prec, NearestEven).unwrap()
+}
+
+// finite doubles over a wide but overflow-free range of exponents
+fn random_f64(rng: &mut XorShift) -> f64 {
+    let bits = rng.next();
+    let exp = 1023 - 300 + (bits >> 52) % 600;
+    f64::from_bits((bits & (1 << 63 | ((1 << 52) - 1))) | exp << 52)
+}
+
+// a result f64 itself rounded only once
+fn comparable(x: f64) -> bool {
+    x == 0.0 || x.is_normal()
+}
+
+#[test]
+fn f64_arithmetic_at_53_bits() {
+    let mut rng = XorShift(0x6A09E667F3BCC908);
+    for _ in 0..2000 {
+        let (x, y) = (random_f64(&mut rng), random_f64(&mut rng));
+        // near-equal operands exercise cancellation
+        let y = if rng.next().is_multiple_of(4) { x * (1.0 + y.fract() * 1e-12) } else { y };
+        let (a, b) = (float(x, 53), float(y, 53));
+        for &(ref got, want) in &[(&a + &b, x + y), (&a - &b, x - y), (&a * &b, x * y), (&a / &b, x / y)] {
+            if comparable(want) {
+                assert_eq!(got.to_f64(), want, "{:e} {:e}", x, y);
+            }
+        }
+        assert_eq!(a.abs().sqrt().to_f64(), x.abs().sqrt(), "sqrt {:e}", x);
+        assert_eq!(a.cmp(&b), x.partial_cmp(&y).unwrap());
+        assert_eq!(a.to_f64(), x);
+    }
+}
+
+#[test]
+fn tiny_addends_still_round() {
+    let one = float(1.0, 53);
+    let tiny = float(1e-300, 53);
+    let sum = one.add_round(&tiny, 53, TowardPositive);
+    assert_eq!(sum.to_f64(), 1.0 + f64::EPSILON);
+    assert_eq!(one.add_round(&tiny, 53, NearestEven), one);
+    assert_eq!(one.sub_round(&tiny, 53, TowardZero).to_f64(), 1.0 - f64::EPSILON / 2.0);
+    assert_eq!((&one + &tiny).with_precision(2000, NearestEven), one);
+    assert!(one.add_round(&tiny, 2000, NearestEven) > one);
+}
+
+#[test]
+fn rounding_modes_bracket_the_exact_value() {
+    let mut rng = XorShift(0xBB67AE8584CAA73B);
+    for _ in 0..300 {
+        let prec = 1 + (rng.next() % 40) as usize;
+        let num = BigInt::from(rng.next() as i64);
+        let den = BigInt::from(1 + (rng.next() >> 20) as i64);
+        let exact = BigRational::new(num.clone(), den.clone());
+        let (a, b) = (BigFloat::from_bigint(&num, 64, NearestEven),
+                      BigFloat::from_bigint(&den, 64, NearestEven));
+
+        let r: Vec<BigFloat> = MODES.iter().map(|&mode| a.div_round(&b, prec, mode)).collect();
+        let q: Vec<BigRational> = r.iter().map(|x| x.to_rational()).collect();
+        for x in &r {
+            assert!(x.mantissa().magnitude().bits() <= prec);
+        }
+        assert!(q[4] <= exact && exact <= q[3]);
+        assert!(q[2].abs() <= exact.abs());
+        assert!(q[2] == q[3] || q[2] == q[4]);
+        for nearest in &q[..2] {
+            assert!(nearest == &q[3] || nearest == &q[4]);
+            assert!((nearest - &exact).abs() <= (&q[3] - &exact).abs());
+            assert!((nearest - &exact).abs() <= (&q[4] - &exact).abs());
+        }
+        if q[3] == q[4] {
+            assert_eq!(q[3], exact);
+        }
+    }
+}
+
+#[test]
+fn ties() {
+    // 9 and 11 at 3 bits are ties between 8 and 10, and 10 and 12
+    for &(n, even, away) in &[(9, 8, 10), (11, 12, 12), (-9, -8, -10)] {
+        let x = BigFloat::from_i64(n, 8);
+        assert_eq!(x.with_precision(3, NearestEven), BigFloat::from_i64(even, 3));
+        assert_eq!(x.with_precision(3, NearestAway), BigFloat::from_i64(away, 3));
+    }
+    assert_eq!(BigFloat::from_i64(-9, 8).with_precision(3, TowardPositive), BigFloat::from_i64(-8, 3));
+    assert_eq!(BigFloat::from_i64(-9, 8).with_precision(3, TowardNegative), BigFloat::from_i64(-10, 3));
+}
+
+#[test]
+fn square_roots() {
+    let two = BigFloat::from_i64(2, 200);
+    let root = two.sqrt();
+    assert!(root.mantissa().magnitude().bits() <= 200);
+    // the root is within half an ulp: |root^2 - 2| < 2^-198
+    let err = (&root.mul_round(&root, 1000, NearestEven) - &two).abs();
+    assert!(err < parse("1e-59", 200));
+    assert_eq!(format!("{:.50}", root), "1.41421356237309504880168872420969807856967187537695");
+
+    assert_eq!(BigFloat::from_i64(1 << 40, 10).sqrt(), BigFloat::from_i64(1 << 20, 10));
+    assert_eq!(float(0.25, 3).sqrt(), float(0.5, 3));
+    assert!(BigFloat::zero(10).sqrt().is_zero());
+}
+
+#[test]
+#[should_panic]
+fn sqrt_of_negative_panics() {
+    BigFloat::from_i64(-1, 10).sqrt();
+}
+
+#[test]
+#[should_panic]
+fn division_by_zero_panics() {
+    let _ = BigFloat::from_i64(1, 10) / BigFloat::zero(10);
+}
+
+#[test]
+fn parsing_matches_f64() {
+    let mut rng = XorShift(0x3C6EF372FE94F82B);
+    for _ in 0..1000 {
+        let x = random_f64(&mut rng);
+        for s in &[format!("{:e}", x), format!("{:.25e}", x), x.to_string()] {
+            assert_eq!(parse(s, 53).to_f64(), s.parse::<f64>().unwrap(), "{}", s);
+        }
+        let digits = rng.next() % 1_000_000_000_000_000_000;
+        let s = format!("{}.{}e{}", digits, rng.next() % 1000, (rng.next() % 600) as i64 - 300);
+        assert_eq!(parse(&s, 53).to_f64(), s.parse::<f64>().unwrap(), "{}", s);
+    }
+    assert_eq!(parse("-0.5", 10), float(-0.5, 10));
+    assert_eq!(parse("+12E-1", 10), parse("1.2", 10));
+    assert!(parse("0.000", 10).is_zero());
+    for bad in &["", ".", "1e", "e5", "1.2.3", "--1", "1,5", "inf"] {
+        assert!(BigFloat::parse(bad, 10, NearestEven).is_err(), "{}", bad);
+    }
+}
+
+#[test]
+fn parsing_rejects_huge_exponents() {
+    // these used to wrap to 10^0 or spend forever on 10^exp
+    for s in &["1e4294967296", "1e-4294967297", "1e9999999999", "5e-9223372036854775808",
+               "1e100001", "2.5e-100001"] {
+        assert_eq!(BigFloat::parse(s, 53, NearestEven).err(),
+                   Some(BigNumError::InvalidExponent), "{}", s);
+    }
+    // the limit grows with the precision
+    assert!(parse("1e100000", 53).exponent() > 332_000);
+    assert!(BigFloat::parse("1e200000", 53, NearestEven).is_err());
+    assert!(!parse("1e200000", 50_000).is_zero());
+    assert!(!parse("1e-100000", 53).is_zero());
+}
+
+#[test]
+fn printing() {
+    assert_eq!(float(0.0, 53).to_string(), "0");
+    assert_eq!(float(1.5, 53).to_string(), "1.5");
+    assert_eq!(float(-1024.0, 53).to_string(), "-1024");
+    assert_eq!(float(1e21, 53).to_string(), "1e+21");
+    assert_eq!(float(0.5f64.powi(30), 24).to_string(), "9.31322575e-10");
+    assert_eq!(float(0.001, 53).to_string(), "0.001");
+    assert_eq!(float(0.001, 10).to_string(), "0.00099945");
+    assert_eq!(parse("1", 100).div(&parse("3", 100)).to_string(),
+               "0.33333333333333333333333333333346");
+    assert_eq!(format!("{:>8}", float(-2.5, 10)), "    -2.5");
+    assert_eq!(format!("{:.3}", float(2.0f64.sqrt(), 53)), "1.414");
+    assert_eq!(format!("{:+.1}", float(0.25, 53)), "+0.3");
+    assert_eq!(format!("{:.2}", float(-0.001, 53)), "0.00");
+
+    let mut rng = XorShift(0xA54FF53A5F1D36F1);
+    for _ in 0..300 {
+        let prec = 1 + (rng.next() % 300) as usize;
+        let x = float(random_f64(&mut rng), 53).mul_round(&float(random_f64(&mut rng), 53), prec, TowardZero);
+        assert_eq!(parse(&x.to_string(), prec), x, "{}", x);
+    }
+}
+
+#[test]
+fn deep_zoom_reference_orbit() {
+    // a point near the boundary of the Mandelbrot set, far too deep for
+    // f64 to tell apart from its neighbours
+    let prec = 200;
+    let cx = parse("-0.743643887037158704752191506114774", prec);
+    let cy = parse("0.131825904205311970493132056385139", prec);
+    let four = BigFloat::from_i64(4, prec);
+
+    let orbit = |cx: &BigFloat| {
+        let (mut x, mut y) = (BigFloat::zero(prec), BigFloat::zero(prec));
+        let mut orbit = Vec::new();
+        for _ in 0..1000 {
+            let (xx, yy) = (&x * &x, &y * &y);
+            if &xx + &yy > four {
+                break;
+            }
+            let xy = &x * &y;
+            y = &(&xy + &xy) + &cy;
+            x = &(&xx - &yy) + cx;
+            orbit.push((x.to_f64(), y.to_f64()));
+        }
+        orbit
+    };
+
+    let reference = orbit(&cx);
+    assert_eq!(reference.len(), 1000);
+    // a neighbour 1e-30 away is indistinguishable in f64 but its orbit
+    // still drifts apart
+    let neighbour = orbit(&(&cx + &parse("1e-30", prec)));
+    assert_eq!(reference[0], neighbour[0]);
+    assert!(reference != neighbour);
+}
@@ -0,0 +1,70 @@
+extern crate bignum;
+use bignum::{BigUint,BigInt};
+
+mod common;
+use common::XorShift;
+
+#[test]
+fn display_and_debug() {
+    let n = BigUint::from_limbs(vec![0, 1]);
+    assert_eq!(format!("{}", n), "18446744073709551616");
+    assert_eq!(format!("{:?}", n), "18446744073709551616");
+    assert_eq!(format!("{}", BigUint::from_u64(0)), "0");
+    assert_eq!(format!("{}", BigInt::from_i64(-42)), "-42");
+    assert_eq!(format!("{:?}", BigInt::from_i64(-42)), "-42");
+}
+
+#[test]
+fn width_fill_and_sign() {
+    let n = BigUint::from_u64(255);
+    let m = BigInt::from_i64(-255);
+    assert_eq!(format!("{:>6}", n), "   255");
+    assert_eq!(format!("{:*<6}", n), "255***");
+    assert_eq!(format!("{:^7}", n), "  255  ");
+    assert_eq!(format!("{:06}", m), "-00255");
+    assert_eq!(format!("{:+}", n), "+255");
+    assert_eq!(format!("{:+}", BigInt::from_i64(0)), "+0");
+    assert_eq!(format!("{:+}", m), "-255");
+    assert_eq!(format!("{:#010x}", n), "0x000000ff");
+}
+
+#[test]
+fn power_of_two_bases() {
+    let n = BigUint::from_u64(0xDEADBEEF);
+    assert_eq!(format!("{:x}", n), "deadbeef");
+    assert_eq!(format!("{:X}", n), "DEADBEEF");
+    assert_eq!(format!("{:#x}", n), "0xdeadbeef");
+    assert_eq!(format!("{:o}", n), format!("{:o}", 0xDEADBEEFu64));
+    assert_eq!(format!("{:#b}", BigUint::from_u64(5)), "0b101");
+    assert_eq!(format!("{:x}", BigInt::from_i64(-255)), "-ff");
+    assert_eq!(format!("{:b}", BigUint::from_u64(0)), "0");
+}
+
+#[test]
+fn power_of_two_bases_match_u128() {
+    let mut rng = XorShift(0x9E3779B97F4A7C15);
+    for _ in 0..100 {
+        let v = (rng.next() as u128) << (rng.next() % 64) | rng.next() as u128;
+        let n = BigUint::from_limbs(vec![v as u64, (v >> 64) as u64]);
+        assert_eq!(format!("{:x}", n), format!("{:x}", v));
+        assert_eq!(format!("{:o}", n), format!("{:o}", v));
+        assert_eq!(format!("{:b}", n), format!("{:b}", v));
+        assert_eq!(format!("{}", n), format!("{}", v));
+    }
+}
+
+#[test]
+fn power_of_two_bases_match_division() {
+    let mut rng = XorShift(0x2545F4914F6CDD1D);
+    let n = rng.biguint(50);
+    for &base in &[2, 4, 8, 16, 32, 64, 128, 256] {
+        let mut expected = Vec::new();
+        let mut rest = n.clone();
+        while !rest.is_zero() {
+            let (quot, rem) = rest.divmod_limb(base);
+            expected.push(rem as u8);
+            rest = quot;
+        }
+        assert_eq!(n.digits(base).unwrap(), expected, "base {}", base);
+    }
+}
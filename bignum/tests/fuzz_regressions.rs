@@ -0,0 +1,42 @@
+extern crate bignum;
+
+mod common;
+use common::XorShift;
+use common::reference::{check_arith,check_arith_named};
+
+use std::fs;
+use std::path::Path;
+
+// hand-written inputs for the arith fuzz target that pin down edge
+// cases it should keep covering; see fuzz/fuzz_targets/arith.rs
+#[test]
+fn fuzz_regressions() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("fuzz/regressions/arith");
+    let mut count = 0;
+    for entry in fs::read_dir(&dir).unwrap() {
+        let path = entry.unwrap().path();
+        check_arith_named(&path.display().to_string(), &fs::read(&path).unwrap());
+        count += 1;
+    }
+    assert!(count > 0, "no inputs in {}", dir.display());
+}
+
+// the fuzz target's check on random inputs, so it runs without cargo-fuzz
+#[test]
+fn random_inputs() {
+    let mut rng = XorShift(0xBB67AE8584CAA73B);
+    for _ in 0..2000 {
+        let len = (rng.next() % 200) as usize;
+        let mut data: Vec<u8> = (0..len).map(|_| rng.next() as u8).collect();
+        // runs of 0x00 and 0xff bytes make the carries interesting
+        if len > 3 && rng.next() & 1 == 0 {
+            let fill = if rng.next() & 1 == 0 { 0 } else { 0xff };
+            let start = 3 + (rng.next() % (len as u64 - 3)) as usize;
+            let end = start + (rng.next() % (len - start) as u64) as usize;
+            for b in &mut data[start..end] {
+                *b = fill;
+            }
+        }
+        check_arith(&data);
+    }
+}
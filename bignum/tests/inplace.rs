@@ -0,0 +1,126 @@
+extern crate bignum;
+use bignum::{BigUint,Scratch};
+
+mod common;
+use common::XorShift;
+
+#[test]
+fn add_assign_matches_add() {
+    let mut rng = XorShift(0x6A09E667F3BCC908);
+    for &(la, lb) in &[(1, 1), (1, 5), (5, 1), (8, 8), (40, 3), (3, 40)] {
+        let a = rng.biguint(la);
+        let b = rng.biguint(lb);
+        let mut sum = a.clone();
+        sum.add_assign(&b);
+        assert_eq!(sum, a.add(&b));
+
+        let mut sum = a.clone();
+        sum += &b;
+        assert_eq!(sum, &a + &b);
+    }
+
+    let mut ones = BigUint::from_limbs(vec![u64::MAX; 4]);
+    ones.add_assign(&BigUint::from_u64(1));
+    assert_eq!(ones.limbs(), vec![0, 0, 0, 0, 1]);
+}
+
+#[test]
+fn sub_assign_reports_underflow() {
+    let mut rng = XorShift(0xBB67AE8584CAA73B);
+    for &(la, lb) in &[(1, 1), (6, 2), (2, 6), (20, 20)] {
+        let a = rng.biguint(la);
+        let b = rng.biguint(lb);
+        let (big, small) = if a >= b { (&a, &b) } else { (&b, &a) };
+        let diff = big - small;
+
+        let mut x = big.clone();
+        assert!(!x.sub_assign(small));
+        assert_eq!(x, diff);
+
+        let mut x = small.clone();
+        assert_eq!(x.sub_assign(big), big != small);
+        assert_eq!(x, diff);
+    }
+
+    let mut x = BigUint::from_limbs(vec![0, 0, 1]);
+    assert!(!x.sub_assign(&BigUint::from_u64(1)));
+    assert_eq!(x.limbs(), vec![u64::MAX, u64::MAX]);
+}
+
+#[test]
+fn mul_add_limb_matches_mul_and_add() {
+    let mut rng = XorShift(0x3C6EF372FE94F82B);
+    for &len in &[1, 2, 7, 30] {
+        let a = rng.biguint(len);
+        for &(m, k) in &[(0, 0), (0, 5), (1, u64::MAX), (10, 7), (u64::MAX, u64::MAX)] {
+            let mut x = a.clone();
+            x.mul_add_limb(m, k);
+            assert_eq!(x, &(&a * m) + k);
+        }
+    }
+}
+
+#[test]
+fn divmod_into_matches_divmod() {
+    let mut rng = XorShift(0xA54FF53A5F1D36F1);
+    let mut q = BigUint::from_u64(0);
+    let mut r = BigUint::from_u64(0);
+    let mut scratch = Scratch::new();
+    for &(la, lb) in &[(1, 1), (5, 1), (3, 5), (10, 2), (40, 17), (64, 63), (20, 20)] {
+        let a = rng.biguint(la);
+        let b = rng.biguint(lb);
+        a.divmod_into(&b, &mut q, &mut r, &mut scratch);
+        let (quot, rem) = a.divmod(&b);
+        assert_eq!(q, quot);
+        assert_eq!(r, rem);
+        assert_eq!(&(&q * &b) + &r, a);
+    }
+}
+
+#[test]
+fn buffers_stop_growing() {
+    // after the first round every buffer is already big enough
+    let mut rng = XorShift(0x510E527FADE682D1);
+    let a = rng.biguint(50);
+    let b = rng.biguint(20);
+    let mut q = BigUint::from_u64(0);
+    let mut r = BigUint::from_u64(0);
+    let mut scratch = Scratch::new();
+    a.divmod_into(&b, &mut q, &mut r, &mut scratch);
+    let caps = (q.capacity(), r.capacity(), scratch.capacity());
+    for _ in 0..10 {
+        a.divmod_into(&b, &mut q, &mut r, &mut scratch);
+        assert_eq!((q.capacity(), r.capacity(), scratch.capacity()), caps);
+    }
+
+    let mut x = BigUint::from_u64(1);
+    let mut y = BigUint::from_u64(1);
+    x.reserve(8);
+    y.reserve(8);
+    let cap = x.capacity();
+    assert_eq!(y.capacity(), cap);
+    for _ in 0..200 {
+        x.add_assign(&y);
+        std::mem::swap(&mut x, &mut y);
+        assert_eq!((x.capacity(), y.capacity()), (cap, cap));
+    }
+    assert_eq!(y.to_string(), "734544867157818093234908902110449296423351");
+}
+
+#[test]
+fn digits_with_reuses_the_scratch() {
+    let mut rng = XorShift(0x9B05688C2B3E6C1F);
+    let mut scratch = Scratch::new();
+    for &len in &[1, 29, 31, 200, 700] {
+        let a = rng.biguint(len);
+        assert_eq!(a.digits_with(10, &mut scratch), a.digits(10));
+        assert_eq!(a.digits_with(7, &mut scratch), a.digits(7));
+    }
+    // the biggest number has already sized every buffer
+    let a = rng.biguint(700);
+    let cap = scratch.capacity();
+    for _ in 0..3 {
+        a.digits_with(10, &mut scratch).unwrap();
+        assert_eq!(scratch.capacity(), cap);
+    }
+}
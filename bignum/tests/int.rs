@@ -0,0 +1,115 @@
+extern crate bignum;
+use bignum::{BigUint,BigInt,IntSign};
+use std::collections::HashSet;
+
+mod common;
+use common::XorShift;
+
+fn big(n: i128) -> BigInt {
+    let sign = if n < 0 { IntSign::Neg } else { IntSign::Pos };
+    let m = n.unsigned_abs();
+    BigInt::from_biguint(sign, BigUint::from_limbs(vec![m as u64, (m >> 64) as u64]))
+}
+
+#[allow(clippy::manual_is_multiple_of)]
+fn random_i128(rng: &mut XorShift) -> i128 {
+    let n = (rng.next() >> (rng.next() % 64)) as i128 * (rng.next() >> 2) as i128;
+    if rng.next() % 2 == 0 { n } else { -n }
+}
+
+#[test]
+fn arithmetic_matches_i128() {
+    let mut rng = XorShift(0x9E3779B97F4A7C15);
+    for _ in 0..500 {
+        let (a, b) = (random_i128(&mut rng), random_i128(&mut rng));
+        let (x, y) = (big(a), big(b));
+        assert!(&x + &y == big(a + b));
+        assert!(&x - &y == big(a - b));
+        if let Some(p) = a.checked_mul(b) {
+            assert!(&x * &y == big(p));
+        }
+        if b != 0 {
+            assert!(&x / &y == big(a / b));
+            assert!(&x % &y == big(a % b));
+            let (q, r) = x.div_mod_floor(&y);
+            let floor = if a % b != 0 && (a < 0) != (b < 0) { a / b - 1 } else { a / b };
+            assert!(q == big(floor));
+            assert!(&q * &y + &r == x);
+            assert!(r.is_zero() || r.is_negative() == y.is_negative());
+        }
+        assert!(x.cmp(&y) == a.cmp(&b));
+        assert!(x.abs() == big(a.abs()));
+        assert!(x.signum() == big(a.signum()));
+    }
+}
+
+#[test]
+fn division_sign_rules() {
+    let cases = [(7, 2), (-7, 2), (7, -2), (-7, -2), (6, 3), (-6, 3), (0, -5)];
+    for &(a, b) in &cases {
+        let (q, r) = BigInt::from_i64(a).div_rem(&BigInt::from_i64(b));
+        assert!(q == BigInt::from_i64(a / b) && r == BigInt::from_i64(a % b));
+        let (q, r) = BigInt::from_i64(a).div_mod_floor(&BigInt::from_i64(b));
+        let fq = (a as f64 / b as f64).floor() as i64;
+        assert!(q == BigInt::from_i64(fq) && r == BigInt::from_i64(a - fq * b));
+    }
+}
+
+#[test]
+fn scalar_operands() {
+    let x = BigInt::from_i64(-100);
+    assert!(&x + 30 == BigInt::from_i64(-70));
+    assert!(&x - (-30) == BigInt::from_i64(-70));
+    assert!(&x * -3 == BigInt::from_i64(300));
+    assert!(-3 * x.clone() == BigInt::from_i64(300));
+    assert!(&x / 7 == BigInt::from_i64(-14));
+    assert!(&x % 7 == BigInt::from_i64(-2));
+    let mut y = x.clone();
+    y += 100;
+    assert!(y.is_zero() && !y.is_negative());
+}
+
+#[test]
+#[allow(clippy::erasing_op)]
+fn zero_has_a_single_sign() {
+    let a = BigInt::from_i64(5);
+    let zero = &a - &a;
+    assert!(zero.sign() == IntSign::Pos);
+    assert!((-zero.clone()).sign() == IntSign::Pos);
+    assert!((BigInt::from_i64(-5) * 0).sign() == IntSign::Pos);
+    assert!((BigInt::from_i64(-4) % 2).sign() == IntSign::Pos);
+    assert!(BigInt::from_biguint(IntSign::Neg, BigUint::from_u64(0)).sign() == IntSign::Pos);
+}
+
+#[test]
+fn parts_round_trip() {
+    let n = big(-(1i128 << 100) - 7);
+    assert_eq!(n.sign(), IntSign::Neg);
+    assert!(n.magnitude() == &BigUint::from_limbs(vec![7, 1 << 36]));
+    let (sign, mag) = n.clone().into_parts();
+    assert!(BigInt::from_biguint(sign, mag) == n);
+
+    // a negative zero cannot be built, so equal values hash alike
+    let zeros = [BigInt::from_biguint(IntSign::Neg, BigUint::from_u64(0)), BigInt::zero()];
+    let set: HashSet<BigInt> = zeros.iter().cloned().collect();
+    assert_eq!(set.len(), 1);
+    assert_eq!(zeros[0].clone().into_parts().0, IntSign::Pos);
+}
+
+#[test]
+#[allow(clippy::legacy_numeric_constants)]
+fn from_i64_min() {
+    let min = BigInt::from_i64(i64::min_value());
+    assert!(min.is_negative());
+    assert!(min.magnitude() == &BigUint::from_u64(1 << 63));
+    assert!(&min - 1 == big(i64::min_value() as i128 - 1));
+}
+
+#[test]
+fn pow() {
+    assert!(BigInt::from_i64(-3).pow(3) == BigInt::from_i64(-27));
+    assert!(BigInt::from_i64(-3).pow(4) == BigInt::from_i64(81));
+    assert!(BigInt::from_i64(-3).pow(0) == BigInt::from_i64(1));
+    assert!(BigInt::from_i64(2).pow(100) == big(1 << 100));
+    assert!(BigUint::from_u64(10).pow(40).to_base_string(10).unwrap().len() == 41);
+}
@@ -0,0 +1,124 @@
+extern crate bignum;
+use bignum::{BigUint,MontgomeryCtx,BarrettCtx};
+
+mod common;
+use common::XorShift;
+
+fn slow_pow(a: &BigUint, exp: &BigUint, m: &BigUint) -> BigUint {
+    let mut result = BigUint::from_u64(1).divmod(m).1;
+    for i in (0..exp.bits()).rev() {
+        result = result.square().divmod(m).1;
+        if (exp >> i).divmod_limb(2).1 == 1 {
+            result = result.mul(a).divmod(m).1;
+        }
+    }
+    result
+}
+
+#[test]
+fn montgomery_matches_divmod() {
+    let mut rng = XorShift(0x9E3779B97F4A7C15);
+    for &len in &[1, 2, 3, 8, 40] {
+        let mut m = rng.biguint(len);
+        if m.limbs()[0].is_multiple_of(2) {
+            m += 1;
+        }
+        let ctx = MontgomeryCtx::new(&m);
+        for _ in 0..10 {
+            let a = rng.biguint(len + 1).divmod(&m).1;
+            let b = rng.biguint(len).divmod(&m).1;
+            let (am, bm) = (ctx.to_montgomery(&a), ctx.to_montgomery(&b));
+            assert_eq!(ctx.from_montgomery(&am), a);
+            assert_eq!(ctx.from_montgomery(&ctx.mul(&am, &bm)), a.mul(&b).divmod(&m).1);
+            assert_eq!(ctx.from_montgomery(&ctx.square(&am)), a.square().divmod(&m).1);
+        }
+        let a = rng.biguint(len);
+        let e = rng.biguint(2);
+        let expected = slow_pow(&a, &e, &m);
+        assert_eq!(ctx.from_montgomery(&ctx.pow(&ctx.to_montgomery(&a), &e)), expected);
+        assert_eq!(a.mod_pow(&e, &m), expected);
+    }
+}
+
+#[test]
+fn montgomery_edge_moduli() {
+    for &m in &[1u64, 3, !0] {
+        let m = BigUint::from_u64(m);
+        let ctx = MontgomeryCtx::new(&m);
+        let a = BigUint::from_u64(12345);
+        let e = BigUint::from_u64(1000003);
+        assert_eq!(ctx.from_montgomery(&ctx.pow(&ctx.to_montgomery(&a), &e)),
+                   slow_pow(&a, &e, &m));
+    }
+    let all_ones = BigUint::from_limbs(vec![!0; 6]);
+    let ctx = MontgomeryCtx::new(&all_ones);
+    let x = &all_ones - 1;
+    let xm = ctx.to_montgomery(&x);
+    assert_eq!(ctx.from_montgomery(&ctx.square(&xm)), BigUint::from_u64(1));
+}
+
+#[test]
+fn montgomery_reduces_out_of_range_inputs() {
+    let mut rng = XorShift(0xD1B54A32D192ED03);
+    for &len in &[1, 2, 7] {
+        let mut m = rng.biguint(len);
+        if m.limbs()[0].is_multiple_of(2) {
+            m += 1;
+        }
+        let ctx = MontgomeryCtx::new(&m);
+        let r_inv = ctx.from_montgomery(&BigUint::from_u64(1));
+        for _ in 0..10 {
+            // well past modulus * R, so redc alone would drop limbs
+            let a = rng.biguint(3 * len + 2);
+            let b = rng.biguint(2 * len + 1);
+            let (ar, br) = (a.divmod(&m).1, b.divmod(&m).1);
+            assert_eq!(ctx.from_montgomery(&a), ar.mul(&r_inv).divmod(&m).1);
+            assert_eq!(ctx.mul(&a, &b), ctx.mul(&ar, &br));
+            assert_eq!(ctx.square(&a), ctx.square(&ar));
+        }
+    }
+}
+
+#[test]
+#[should_panic]
+fn montgomery_rejects_even_moduli() {
+    MontgomeryCtx::new(&BigUint::from_u64(10));
+}
+
+#[test]
+fn barrett_matches_divmod() {
+    let mut rng = XorShift(0x2545F4914F6CDD1D);
+    for &len in &[1, 2, 5, 33, 70] {
+        let m = rng.biguint(len);
+        let ctx = BarrettCtx::new(&m);
+        for _ in 0..10 {
+            let x = rng.biguint(2 * len).divmod(&m.square()).1;
+            assert_eq!(ctx.reduce(&x), x.divmod(&m).1);
+            let a = rng.biguint(len).divmod(&m).1;
+            let b = rng.biguint(len).divmod(&m).1;
+            assert_eq!(ctx.mul(&a, &b), a.mul(&b).divmod(&m).1);
+            assert_eq!(ctx.square(&a), a.square().divmod(&m).1);
+        }
+        let a = rng.biguint(len + 3);
+        let e = rng.biguint(1);
+        assert_eq!(ctx.pow(&a, &e), slow_pow(&a, &e, &m));
+    }
+    // inputs past modulus^2, and products of unreduced inputs
+    for &len in &[1, 3, 9] {
+        let m = rng.biguint(len);
+        let ctx = BarrettCtx::new(&m);
+        for _ in 0..10 {
+            let x = rng.biguint(4 * len + 1);
+            assert_eq!(ctx.reduce(&x), x.divmod(&m).1);
+            let a = rng.biguint(2 * len);
+            let b = rng.biguint(len + 2);
+            assert_eq!(ctx.mul(&a, &b), a.mul(&b).divmod(&m).1);
+            assert_eq!(ctx.square(&a), a.square().divmod(&m).1);
+        }
+    }
+    // even moduli go through divmod in mod_pow
+    let m = BigUint::from_u64(1 << 40) * 6;
+    let a = BigUint::from_u64(987654321);
+    let e = BigUint::from_u64(65537);
+    assert_eq!(BarrettCtx::new(&m).pow(&a, &e), a.mod_pow(&e, &m));
+}
@@ -0,0 +1,81 @@
+extern crate bignum;
+use bignum::BigUint;
+
+mod common;
+use common::XorShift;
+
+fn check_mul(rng: &mut XorShift, la: usize, lb: usize) {
+    let a = rng.biguint(la);
+    let b = rng.biguint(lb);
+    let expected = a.mul_schoolbook(&b);
+    assert_eq!(a.mul(&b).limbs(), expected.limbs(), "{} x {} limbs", la, lb);
+    assert_eq!(b.mul(&a).limbs(), expected.limbs(), "{} x {} limbs", lb, la);
+}
+
+#[test]
+fn mul_matches_schoolbook_balanced() {
+    let mut rng = XorShift(0x9E3779B97F4A7C15);
+    for &len in &[1, 2, 31, 32, 33, 47, 64, 100, 159, 160, 161, 250, 500, 777] {
+        check_mul(&mut rng, len, len);
+    }
+}
+
+#[test]
+fn mul_matches_schoolbook_unbalanced() {
+    let mut rng = XorShift(0x2545F4914F6CDD1D);
+    for &(la, lb) in &[(1, 300), (33, 100), (40, 81), (100, 199), (170, 400),
+                       (200, 301), (300, 1000), (480, 500)] {
+        check_mul(&mut rng, la, lb);
+    }
+}
+
+#[test]
+fn mul_all_ones() {
+    for &len in &[35, 170, 600] {
+        let a = BigUint::from_limbs(vec![!0; len]);
+        assert_eq!(a.mul(&a).limbs(), a.mul_schoolbook(&a).limbs());
+        assert_eq!(a.square().limbs(), a.mul_schoolbook(&a).limbs());
+    }
+}
+
+#[test]
+fn square_matches_schoolbook() {
+    let mut rng = XorShift(0xD1B54A32D192ED03);
+    for &len in &[1, 2, 3, 47, 48, 49, 100, 191, 192, 193, 400, 901] {
+        let a = rng.biguint(len);
+        assert_eq!(a.square().limbs(), a.mul_schoolbook(&a).limbs(), "{} limbs", len);
+    }
+}
+
+#[test]
+fn mul_by_zero() {
+    let mut rng = XorShift(1);
+    let a = rng.biguint(200);
+    let zero = BigUint::from_u64(0);
+    assert!(a.mul(&zero).is_zero());
+    assert!(zero.mul(&a).is_zero());
+    assert!(zero.square().is_zero());
+}
+
+#[test]
+fn limb_products_match_u128() {
+    let edges = [0, 1, 2, 0xFFFF_FFFF, 1 << 63, u64::MAX - 1, u64::MAX];
+    for &a in &edges {
+        for &b in &edges {
+            let wide = (a as u128) * (b as u128);
+            let product = BigUint::from_u64(a).mul(&BigUint::from_u64(b));
+            let mut expected = vec![wide as u64, (wide >> 64) as u64];
+            if expected[1] == 0 {
+                expected.pop();
+            }
+            assert_eq!(product.limbs(), expected, "{} x {}", a, b);
+        }
+    }
+
+    // all-ones operands push a carry through every limb
+    let ones = BigUint::from_limbs(vec![u64::MAX; 40]);
+    let sq = ones.mul(&ones);
+    assert_eq!(sq.limbs(), ones.mul_schoolbook(&ones).limbs());
+    assert_eq!(sq.limbs()[0], 1);
+    assert_eq!(sq.limbs()[79], u64::MAX);
+}
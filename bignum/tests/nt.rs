@@ -0,0 +1,128 @@
+extern crate bignum;
+use bignum::{BigUint,BigInt};
+
+mod common;
+use common::XorShift;
+
+fn gcd_u128(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        let t = a % b;
+        a = b;
+        b = t;
+    }
+    a
+}
+
+fn big(n: u128) -> BigUint {
+    BigUint::from_limbs(vec![n as u64, (n >> 64) as u64])
+}
+
+fn mod_pow_u128(mut b: u128, mut e: u128, m: u128) -> u128 {
+    // operands stay below 2^64, so the products fit
+    let mut r = 1 % m;
+    b %= m;
+    while e > 0 {
+        if e & 1 == 1 {
+            r = r * b % m;
+        }
+        b = b * b % m;
+        e >>= 1;
+    }
+    r
+}
+
+#[test]
+fn bits_and_trailing_zeros() {
+    assert_eq!(BigUint::from_u64(0).bits(), 0);
+    assert_eq!(BigUint::from_u64(1).bits(), 1);
+    assert_eq!(BigUint::from_u64(1 << 63).bits(), 64);
+    assert_eq!(BigUint::from_u64(!0).bits(), 64);
+    assert_eq!(BigUint::from_limbs(vec![0, 1, 0]).bits(), 65);
+    assert_eq!(BigUint::from_u64(0).trailing_zeros(), None);
+    assert_eq!(BigUint::from_limbs(vec![0, 8]).trailing_zeros(), Some(67));
+}
+
+#[test]
+fn gcd_matches_u128() {
+    let mut rng = XorShift(0x9E3779B97F4A7C15);
+    for _ in 0..300 {
+        let k = (rng.next() >> (rng.next() % 64)) as u128;
+        let a = k * (rng.next() >> (rng.next() % 64)) as u128;
+        let b = k * (rng.next() >> (rng.next() % 64)) as u128;
+        assert_eq!(big(a).gcd(&big(b)), big(gcd_u128(a, b)));
+    }
+    assert_eq!(big(0).gcd(&big(0)), big(0));
+    assert_eq!(big(12).lcm(&big(18)), big(36));
+}
+
+#[test]
+fn gcd_of_big_multiples() {
+    let mut rng = XorShift(0x2545F4914F6CDD1D);
+    let g = rng.biguint(5);
+    let a = &g * &rng.biguint(20);
+    let b = &g * &rng.biguint(3);
+    let d = a.gcd(&b);
+    assert!((&d % &g).is_zero());
+    assert!((&a % &d).is_zero() && (&b % &d).is_zero());
+    assert_eq!((&a / &d).gcd(&(&b / &d)), big(1));
+}
+
+#[test]
+#[allow(clippy::manual_is_multiple_of)]
+fn extended_gcd_identity() {
+    let mut rng = XorShift(11);
+    for _ in 0..50 {
+        let mut a = BigInt::from_biguint(bignum::IntSign::Pos, rng.biguint(3));
+        let mut b = BigInt::from_biguint(bignum::IntSign::Pos, rng.biguint(2));
+        if rng.next() % 2 == 0 { a = -a; }
+        if rng.next() % 2 == 0 { b = -b; }
+        let (g, x, y) = a.extended_gcd(&b);
+        assert_eq!(&(&a * &x) + &(&b * &y), g);
+        assert_eq!(g, a.gcd(&b));
+    }
+}
+
+#[test]
+fn mod_pow_matches_u128() {
+    let mut rng = XorShift(3);
+    for _ in 0..200 {
+        let b = rng.next() as u128;
+        let e = (rng.next() >> (rng.next() % 64)) as u128;
+        let m = (rng.next() >> (rng.next() % 63)) as u128 | 1;
+        assert_eq!(big(b).mod_pow(&big(e), &big(m)), big(mod_pow_u128(b, e, m)));
+    }
+    assert_eq!(big(5).mod_pow(&big(0), &big(1)), big(0));
+}
+
+#[test]
+fn mod_pow_fermat() {
+    // 2^127 - 1 is prime, so a^(p-1) == 1
+    let p = big((1 << 127) - 1);
+    let mut rng = XorShift(5);
+    for _ in 0..5 {
+        let a = rng.biguint(2);
+        let e = &p - 1;
+        assert_eq!(a.mod_pow(&e, &p), big(1));
+        let inv = a.mod_inverse(&p).unwrap();
+        assert_eq!(&(&a * &inv) % &p, big(1));
+    }
+    assert!(big(6).mod_inverse(&big(9)).is_none());
+}
+
+#[test]
+fn roots() {
+    let mut rng = XorShift(7);
+    for _ in 0..50 {
+        let n = rng.biguint(4);
+        let s = n.isqrt();
+        assert!(s.square() <= n && (&s + 1).square() > n);
+        for k in 3..7 {
+            let r = n.nth_root(k);
+            assert!(r.pow(k) <= n && (&r + 1).pow(k) > n);
+        }
+    }
+    assert_eq!(big(0).isqrt(), big(0));
+    assert_eq!(big(15).isqrt(), big(3));
+    assert_eq!(big(16).isqrt(), big(4));
+    assert_eq!(big(1 << 120).nth_root(3), big(1 << 40));
+}
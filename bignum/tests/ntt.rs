@@ -0,0 +1,82 @@
+extern crate bignum;
+use bignum::BigUint;
+
+mod common;
+use common::XorShift;
+
+// past the transform thresholds (50000 limbs, for squares too), where
+// schoolbook is too slow to compare against; the products are checked
+// against ones assembled from halves below the thresholds instead
+
+fn low_limbs(x: &BigUint, n: usize) -> BigUint {
+    BigUint::from_limbs(x.limbs()[..n.min(x.limbs().len())].to_vec())
+}
+
+// a*b from (a_hi*2^(64h) + a_lo)*b, every piece under the threshold
+fn mul_by_halves(a: &BigUint, b: &BigUint) -> BigUint {
+    let h = a.limbs().len() / 2;
+    let (lo, hi) = (low_limbs(a, h), a >> (64 * h));
+    &(&hi.mul(b) << (64 * h)) + &lo.mul(b)
+}
+
+#[test]
+fn ntt_mul_matches_halves() {
+    let mut rng = XorShift(0x5851F42D4C957F2D);
+    for &(la, lb) in &[(60000, 60000), (50000, 50001), (70000, 52000)] {
+        let a = rng.biguint(la);
+        let b = rng.biguint(lb);
+        let expected = mul_by_halves(&a, &b);
+        assert_eq!(a.mul(&b), expected, "{} x {} limbs", la, lb);
+        assert_eq!(b.mul(&a), expected, "{} x {} limbs", lb, la);
+    }
+}
+
+#[test]
+fn ntt_square_matches_halves() {
+    let mut rng = XorShift(0x14057B7EF767814F);
+    for &len in &[50000, 60001] {
+        let a = rng.biguint(len);
+        assert_eq!(a.square(), mul_by_halves(&a, &a), "{} limbs", len);
+    }
+}
+
+#[test]
+fn ntt_all_ones() {
+    // (2^(64n) - 1)^2 = 2^(128n) - 2^(64n+1) + 1
+    for &len in &[50000, 60000] {
+        let a = BigUint::from_limbs(vec![!0; len]);
+        let one = BigUint::from_u64(1);
+        let expected = &(&(&one << (128 * len)) - &(&one << (64 * len + 1))) + &one;
+        assert_eq!(a.square(), expected, "{} limbs", len);
+        assert_eq!(a.mul(&a.clone()), expected, "{} limbs", len);
+    }
+}
+
+#[test]
+fn ntt_near_max_limbs() {
+    // long carry chains through the CRT reconstruction
+    let mut rng = XorShift(0xD1342543DE82EF95);
+    let mut limbs = vec![!0u64; 55000];
+    for _ in 0..50 {
+        let i = (rng.next() % 55000) as usize;
+        limbs[i] = rng.next();
+    }
+    let a = BigUint::from_limbs(limbs);
+    let b = rng.biguint(55000);
+    assert_eq!(a.mul(&b), mul_by_halves(&a, &b));
+}
+
+#[test]
+fn ntt_unbalanced_residues() {
+    // chunked into transform-sized products; checked modulo small primes
+    let mut rng = XorShift(0x9E3779B97F4A7C15);
+    let a = rng.biguint(160000);
+    let b = rng.biguint(50000);
+    let product = a.mul(&b);
+    assert_eq!(product.limbs().len(), 210000);
+    for &p in &[0xFFFFFFFFFFFFFFC5u64, 0xFFFFFFFB, 1000000007] {
+        let (ra, rb) = (a.divmod_limb(p).1, b.divmod_limb(p).1);
+        let expected = (ra as u128 * rb as u128 % p as u128) as u64;
+        assert_eq!(product.divmod_limb(p).1, expected, "mod {}", p);
+    }
+}
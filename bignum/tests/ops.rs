@@ -0,0 +1,129 @@
+extern crate bignum;
+use bignum::{BigUint,BigInt,IntSign};
+use std::collections::HashSet;
+
+mod common;
+use common::XorShift;
+
+fn big(n: u128) -> BigUint {
+    BigUint::from_limbs(vec![n as u64, (n >> 64) as u64])
+}
+
+#[test]
+fn operators_match_u128() {
+    let mut rng = XorShift(0x9E3779B97F4A7C15);
+    for _ in 0..200 {
+        let a = rng.next() as u128 * (rng.next() >> (rng.next() % 64)) as u128;
+        let b = (rng.next() >> (rng.next() % 64)) as u128 + 1;
+        let (x, y) = (big(a), big(b));
+        assert!(&x + &y == big(a + b));
+        if let Some(p) = a.checked_mul(b) {
+            assert!(&x * &y == big(p));
+        }
+        assert!(&x / &y == big(a / b));
+        assert!(&x % &y == big(a % b));
+        if a >= b {
+            assert!(&x - &y == big(a - b));
+        }
+        let s = (b % 64) as usize;
+        assert!(&x >> s == big(a >> s));
+        assert!(&x >> 64 == big(a >> 64));
+        assert!(&y << s == big(b << s));
+        assert!(x.cmp(&y) == a.cmp(&b));
+    }
+}
+
+#[test]
+fn owned_borrowed_and_assign_forms_agree() {
+    let mut rng = XorShift(0x2545F4914F6CDD1D);
+    let a = rng.biguint(40);
+    let b = rng.biguint(13);
+    let expected = &a * &b;
+    assert!(a.clone() * b.clone() == expected);
+    assert!(a.clone() * &b == expected);
+    assert!(&a * b.clone() == expected);
+    let mut c = a.clone();
+    c *= &b;
+    assert!(c == expected);
+    c /= b.clone();
+    assert!(c == a);
+    c -= &a;
+    assert!(c == BigUint::from_u64(0));
+    c += a.clone();
+    c <<= 100;
+    c >>= 100;
+    assert!(c == a);
+    c %= &b;
+    assert!(c == a.divmod(&b).1);
+}
+
+#[test]
+#[allow(clippy::erasing_op)]
+fn scalar_operands() {
+    let mut rng = XorShift(5);
+    let a = rng.biguint(9);
+    let k = rng.next();
+    let big_k = BigUint::from_u64(k);
+    assert!(&a + k == &a + &big_k);
+    assert!(k + &a == &a + &big_k);
+    assert!(&a - k == &a - &big_k);
+    assert!(&a * k == &a * &big_k);
+    assert!(k * a.clone() == &a * &big_k);
+    assert!(&a / k == &a / &big_k);
+    assert!(&a % k == &a % &big_k);
+    assert!(a.clone() * 0 == BigUint::from_u64(0));
+
+    let mut fact = BigUint::from_u64(1);
+    for i in 1..21 {
+        fact *= i;
+    }
+    assert!(fact == BigUint::from_u64(2432902008176640000));
+}
+
+#[test]
+#[should_panic]
+fn sub_underflow_panics() {
+    let _ = BigUint::from_u64(1) - BigUint::from_u64(2);
+}
+
+#[test]
+#[should_panic]
+fn sub_method_is_the_operator() {
+    use std::ops::Sub;
+    assert!(big(5).sub(&big(3)) == big(2));
+    let _ = big(1).sub(&big(2));
+}
+
+#[test]
+fn signed_sub() {
+    let d = big(1).signed_sub(&big(1 << 70));
+    assert!(d.sign() == IntSign::Neg && d.magnitude() == &big((1 << 70) - 1));
+    let d = big(3).signed_sub(&big(3));
+    assert!(d.sign() == IntSign::Pos && d.is_zero());
+}
+
+#[test]
+fn leading_zero_limbs_are_dropped() {
+    let a = BigUint::from_limbs(vec![7, 0, 0]);
+    let b = BigUint::from_u64(7);
+    assert!(a == b);
+    assert!(a.limbs() == [7]);
+    assert!(BigUint::from_limbs(vec![]) == BigUint::from_u64(0));
+    assert!(BigUint::from_limbs(vec![1, 0]) < BigUint::from_limbs(vec![0, 1]));
+    let mut set = HashSet::new();
+    set.insert(a);
+    assert!(set.contains(&b));
+}
+
+#[test]
+fn signed_comparison() {
+    let minus_two = BigInt::from_i64(-2);
+    let minus_one = BigInt::from_i64(-1);
+    let zero = BigInt::from_i64(0);
+    let neg_zero = BigInt::from_biguint(IntSign::Neg, BigUint::from_u64(0));
+    let one = BigInt::from_i64(1);
+    assert!(minus_two < minus_one && minus_one < zero && zero < one);
+    assert!(zero == neg_zero);
+    assert!(-one.clone() == minus_one);
+    assert!(-&minus_one == one);
+}
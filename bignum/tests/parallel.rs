@@ -0,0 +1,62 @@
+#![cfg(feature = "parallel")]
+
+extern crate bignum;
+use bignum::BigUint;
+
+mod common;
+use common::XorShift;
+
+use std::sync::{Mutex,PoisonError};
+
+// the thread budget is global, so the tests take turns changing it
+static BUDGET: Mutex<()> = Mutex::new(());
+
+// every check runs with one thread and with several, even on a single
+// core, and the two have to agree to the bit
+fn both<T: PartialEq + std::fmt::Debug, F: Fn() -> T>(f: F) -> T {
+    let _turn = BUDGET.lock().unwrap_or_else(PoisonError::into_inner);
+    bignum::set_max_threads(1);
+    let serial = f();
+    bignum::set_max_threads(8);
+    let parallel = f();
+    assert!(serial == parallel);
+    parallel
+}
+
+// a*b mod p checked through the residues, independently of the product
+fn check_product(a: &BigUint, b: &BigUint, product: &BigUint) {
+    for &p in &[0xFFFFFFFFFFFFFFC5, 0xFFFFFFFFFFFFFF43, 1_000_000_007] {
+        let (x, y) = (a.divmod_limb(p).1 as u128, b.divmod_limb(p).1 as u128);
+        assert_eq!(product.divmod_limb(p).1 as u128, x * y % p as u128);
+    }
+}
+
+#[test]
+fn parallel_products() {
+    let mut rng = XorShift(0x6C44198C4A475817);
+    for &(la, lb) in &[(1500, 1500), (3000, 2500), (5000, 1200), (20000, 1100), (4000, 40)] {
+        let (a, b) = (rng.biguint(la), rng.biguint(lb));
+        let product = both(|| &a * &b);
+        check_product(&a, &b, &product);
+        let square = both(|| a.square());
+        check_product(&a, &a, &square);
+    }
+}
+
+#[test]
+fn parallel_conversion_and_division() {
+    let mut rng = XorShift(0x5FCB6FAB3AD6FAEC);
+    let n = rng.biguint(6000);
+    let digits = both(|| n.to_string());
+    assert_eq!(both(|| BigUint::from_base_string(&digits, 10).unwrap()), n);
+    let d = rng.biguint(2500);
+    let (q, r) = both(|| n.divmod(&d));
+    assert!(r < d);
+    assert_eq!(&(&q * &d) + &r, n);
+}
+
+#[test]
+fn parallel_factorial() {
+    let fact = both(|| BigUint::factorial(30000));
+    assert_eq!(fact, BigUint::factorial(29999) * 30000);
+}
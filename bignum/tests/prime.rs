@@ -0,0 +1,109 @@
+extern crate bignum;
+use bignum::BigUint;
+
+#[allow(clippy::manual_is_multiple_of)]
+fn naive_is_prime(n: u64) -> bool {
+    if n < 2 {
+        return false;
+    }
+    let mut d = 2;
+    while d * d <= n {
+        if n % d == 0 {
+            return false;
+        }
+        d += 1;
+    }
+    true
+}
+
+fn pow2(n: usize) -> BigUint {
+    BigUint::from_u64(1) << n
+}
+
+#[test]
+fn small_numbers() {
+    for n in 0..20000 {
+        let big = BigUint::from_u64(n);
+        assert_eq!(big.is_prime(), naive_is_prime(n), "{}", n);
+        assert_eq!(big.is_probable_prime(5), naive_is_prime(n), "{}", n);
+    }
+    for n in (1 << 20)..(1 << 20) + 3000 {
+        assert_eq!(BigUint::from_u64(n).is_prime(), naive_is_prime(n), "{}", n);
+    }
+}
+
+#[test]
+fn pseudoprimes_are_rejected() {
+    // Carmichael numbers, strong pseudoprimes to base 2, and to the
+    // first few prime bases
+    let cases = [561, 1105, 41041, 825265, 2047, 3277, 4033, 3215031751,
+                 2152302898747, 3474749660383, 341550071728321,
+                 3825123056546413051];
+    for &n in &cases {
+        let big = BigUint::from_u64(n);
+        assert!(!big.is_prime(), "{}", n);
+        assert!(!big.is_probable_prime(10), "{}", n);
+    }
+    assert!(BigUint::from_u64(2047).is_strong_probable_prime(&BigUint::from_u64(2)));
+}
+
+#[test]
+fn strong_probable_prime_reduces_the_base() {
+    let n = BigUint::from_u64(2047);
+    assert!(!n.is_strong_probable_prime(&n));
+    assert!(!n.is_strong_probable_prime(&(&n * 3)));
+    assert!(n.is_strong_probable_prime(&(&n + 2)));
+    assert!(!n.is_strong_probable_prime(&(&n + 3)));
+
+    let p = BigUint::from_u64(1000003);
+    assert!(!p.is_strong_probable_prime(&p));
+    assert!(p.is_strong_probable_prime(&(&p * 5 + 2)));
+}
+
+#[test]
+fn strong_lucas() {
+    // the strong Lucas pseudoprimes below 20000 pass, as they should
+    for &n in &[5459, 5777, 10877, 16109, 18971] {
+        assert!(BigUint::from_u64(n).is_strong_lucas_probable_prime(), "{}", n);
+    }
+    let mut count = 0;
+    for n in (5..20000).filter(|n| n % 2 == 1) {
+        let lucas = BigUint::from_u64(n).is_strong_lucas_probable_prime();
+        if lucas && !naive_is_prime(n) {
+            count += 1;
+        }
+        if naive_is_prime(n) {
+            assert!(lucas, "{}", n);
+        }
+    }
+    assert_eq!(count, 5);
+}
+
+#[test]
+fn big_primes() {
+    for &e in &[61, 89, 107, 127, 521, 607] {
+        let m = &pow2(e) - 1;
+        assert!(m.is_prime(), "2^{} - 1", e);
+        assert!(m.is_probable_prime(10), "2^{} - 1", e);
+    }
+    for &e in &[67, 128, 257, 523] {
+        assert!(!(&pow2(e) - 1).is_prime(), "2^{} - 1", e);
+    }
+    assert!(!(&pow2(128) + 1).is_prime());
+    let p = &pow2(127) - 1;
+    assert!(!(&p * &p).is_prime());
+    assert!(!(&p * &(&pow2(89) - 1)).is_prime());
+}
+
+#[test]
+fn next_and_prev_prime() {
+    assert_eq!(BigUint::from_u64(0).next_prime(), BigUint::from_u64(2));
+    assert_eq!(BigUint::from_u64(2).next_prime(), BigUint::from_u64(3));
+    assert_eq!(BigUint::from_u64(7).next_prime(), BigUint::from_u64(11));
+    assert_eq!(BigUint::from_u64(2).prev_prime(), None);
+    assert_eq!(BigUint::from_u64(3).prev_prime(), Some(BigUint::from_u64(2)));
+    assert_eq!(BigUint::from_u64(100).prev_prime(), Some(BigUint::from_u64(97)));
+    assert_eq!(pow2(64).next_prime(), &pow2(64) + 13);
+    assert_eq!(pow2(64).prev_prime(), Some(&pow2(64) - 59));
+    assert_eq!(pow2(128).next_prime(), &pow2(128) + 51);
+}
@@ -0,0 +1,117 @@
+extern crate bignum;
+use bignum::BigUint;
+
+mod common;
+use common::XorShift;
+
+const ROUNDS: usize = 300;
+
+// operands of every shape the kernels special-case: zero, single limbs,
+// powers of two, runs of all-ones limbs and random limbs, at lengths on
+// both sides of the multiplication and division thresholds
+fn operand(rng: &mut XorShift) -> BigUint {
+    let len = match rng.next() % 4 {
+        0 => 1 + (rng.next() % 3) as usize,
+        1 => 1 + (rng.next() % 40) as usize,
+        _ => 1 + (rng.next() % 400) as usize,
+    };
+    match rng.next() % 6 {
+        0 => BigUint::from_u64(rng.next() % 3),
+        1 => &BigUint::from_u64(1) << (rng.next() % (64 * len as u64)) as usize,
+        2 => BigUint::from_limbs(vec![!0; len]),
+        3 => {
+            // all ones except for a few random limbs
+            let mut limbs = vec![!0u64; len];
+            for _ in 0..3 {
+                limbs[(rng.next() % len as u64) as usize] = rng.next();
+            }
+            BigUint::from_limbs(limbs)
+        }
+        _ => {
+            let n = rng.biguint(len);
+            &n >> (rng.next() % 64) as usize
+        }
+    }
+}
+
+#[test]
+fn add_then_sub_is_identity() {
+    let mut rng = XorShift(0x3C6EF372FE94F82B);
+    for _ in 0..ROUNDS {
+        let (a, b) = (operand(&mut rng), operand(&mut rng));
+        let sum = &a + &b;
+        sum.assert_invariants();
+        assert_eq!(&sum - &b, a);
+        assert_eq!(&sum - &a, b);
+        assert_eq!(&b + &a, sum);
+    }
+}
+
+#[test]
+fn mul_then_div_is_identity() {
+    let mut rng = XorShift(0xA54FF53A5F1D36F1);
+    for _ in 0..ROUNDS {
+        let (a, b) = (operand(&mut rng), operand(&mut rng));
+        let product = &a * &b;
+        product.assert_invariants();
+        assert_eq!(&b * &a, product);
+        if !b.is_zero() {
+            let (q, r) = product.divmod(&b);
+            assert_eq!(q, a);
+            assert!(r.is_zero());
+        }
+    }
+}
+
+#[test]
+fn division_identity() {
+    let mut rng = XorShift(0x510E527FADE682D1);
+    for _ in 0..ROUNDS {
+        let (n, d) = (operand(&mut rng), operand(&mut rng));
+        if d.is_zero() {
+            continue;
+        }
+        let (q, r) = n.divmod(&d);
+        q.assert_invariants();
+        r.assert_invariants();
+        assert!(r < d);
+        assert_eq!(&(&q * &d) + &r, n);
+    }
+}
+
+#[test]
+fn shift_round_trips() {
+    let mut rng = XorShift(0x9B05688C2B3E6C1F);
+    for _ in 0..ROUNDS {
+        let a = operand(&mut rng);
+        let s = (rng.next() % 300) as usize;
+
+        let up = &a << s;
+        up.assert_invariants();
+        assert_eq!(up.highest_bit(), if a.is_zero() { 0 } else { a.highest_bit() + s });
+        assert_eq!(&up >> s, a);
+
+        // shifting right drops exactly the low s bits
+        let down = &a >> s;
+        down.assert_invariants();
+        let low = &a - &(&down << s);
+        assert!(low.highest_bit() <= s);
+        assert_eq!(down.highest_bit(), a.highest_bit().saturating_sub(s));
+
+        let mut n = a.clone();
+        n.bit_shift_left(s);
+        n.bit_shift_right(s);
+        n.assert_invariants();
+        assert_eq!(n, a);
+    }
+}
+
+#[test]
+fn highest_bit_of_single_limbs() {
+    assert_eq!(BigUint::highest_u64_bit(0), 0);
+    for i in 0..64 {
+        assert_eq!(BigUint::highest_u64_bit(1 << i), i + 1);
+        assert_eq!(BigUint::highest_u64_bit(!0 >> (63 - i)), i + 1);
+    }
+    assert_eq!(BigUint::from_limbs(vec![0, !0]).highest_bit(), 128);
+}
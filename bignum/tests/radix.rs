@@ -0,0 +1,83 @@
+extern crate bignum;
+use bignum::BigUint;
+
+mod common;
+use common::XorShift;
+
+// one digit at a time, as a slow reference
+fn naive_digits(n: &BigUint, base: u64) -> Vec<u8> {
+    let mut rest = n.clone();
+    let mut result = Vec::new();
+    while !rest.is_zero() {
+        let (quot, rem) = rest.divmod_limb(base);
+        result.push(rem as u8);
+        rest = quot;
+    }
+    if result.is_empty() {
+        result.push(0);
+    }
+    result
+}
+
+#[test]
+fn small_values() {
+    assert_eq!(BigUint::from_u64(0).to_base_string(10).unwrap(), "0");
+    assert_eq!(BigUint::from_u64(255).to_base_string(16).unwrap(), "FF");
+    assert_eq!(BigUint::from_u64(5).to_base_string(2).unwrap(), "101");
+    let two64 = BigUint::from_limbs(vec![0, 1]);
+    assert_eq!(two64.to_base_string(10).unwrap(), "18446744073709551616");
+    assert_eq!(BigUint::from_str_radix("18446744073709551616", 10).unwrap().limbs(),
+               two64.limbs());
+    assert_eq!(BigUint::from_str_radix("ff", 16).unwrap().limbs(), vec![255]);
+    assert_eq!(BigUint::from_str_radix("000", 10).unwrap().limbs(), vec![0]);
+}
+
+#[test]
+fn digits_match_naive() {
+    let mut rng = XorShift(0x9E3779B97F4A7C15);
+    for &len in &[1, 2, 29, 30, 31, 64, 65, 150, 400, 1100] {
+        let n = rng.biguint(len);
+        for &base in &[2, 3, 10, 16, 37, 64, 255, 256] {
+            assert_eq!(n.digits(base).unwrap(), naive_digits(&n, base),
+                       "{} limbs in base {}", len, base);
+        }
+    }
+}
+
+#[test]
+fn digits_of_powers_of_the_base() {
+    // plenty of zero digits in the low halves
+    let mut n = BigUint::from_u64(1);
+    for _ in 0..3000 {
+        n.mul_by_limb(10);
+    }
+    let s = n.to_base_string(10).unwrap();
+    assert_eq!(s.len(), 3001);
+    assert!(s.starts_with('1') && s[1..].bytes().all(|c| c == b'0'));
+    assert_eq!(BigUint::from_base_string(&s, 10).unwrap().limbs(), n.limbs());
+}
+
+#[test]
+fn round_trip() {
+    let mut rng = XorShift(0x2545F4914F6CDD1D);
+    for &len in &[1, 3, 30, 77, 300, 1500] {
+        let n = rng.biguint(len);
+        for base in 2..65 {
+            let s = n.to_base_string(base).unwrap();
+            let parsed = BigUint::from_base_string(&s, base).unwrap();
+            assert_eq!(parsed.limbs(), n.limbs(), "{} limbs in base {}", len, base);
+        }
+    }
+}
+
+#[test]
+fn parse_errors() {
+    assert!(BigUint::from_str_radix("", 10).is_err());
+    assert!(BigUint::from_str_radix("12a", 10).is_err());
+    assert!(BigUint::from_str_radix("-1", 10).is_err());
+    assert!(BigUint::from_str_radix("10", 1).is_err());
+    assert!(BigUint::from_str_radix("10", 65).is_err());
+    assert!(BigUint::from_str_radix("z", 36).is_ok());
+    assert!(BigUint::from_str_radix("z", 62).is_ok());
+    assert!(BigUint::from_str_radix("Z", 35).is_err());
+}
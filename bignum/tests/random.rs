@@ -0,0 +1,95 @@
+extern crate bignum;
+use bignum::{BigUint,BigInt,Rng,Xoshiro256};
+
+mod common;
+use common::XorShift;
+
+use std::convert::TryFrom;
+
+#[test]
+fn xoshiro_is_reproducible() {
+    // SplitMix64 from 0, then xoshiro256**, as in the reference code
+    let mut rng = Xoshiro256::new(0);
+    assert_eq!(rng.next_u64(), 0x99EC5F36CB75F2B4);
+    assert_eq!(rng.next_u64(), 0xBF6E1F784956452A);
+    assert_eq!(rng.next_u64(), 0x1A5F849D4933E6E0);
+
+    let a: Vec<u64> = (0..10).scan(Xoshiro256::new(42), |r, _| Some(r.next_u64())).collect();
+    let b: Vec<u64> = (0..10).scan(Xoshiro256::new(42), |r, _| Some(r.next_u64())).collect();
+    assert_eq!(a, b);
+    assert!(Xoshiro256::new(43).next_u64() != a[0]);
+}
+
+#[test]
+fn random_bits_fill_the_width() {
+    let mut rng = Xoshiro256::new(1);
+    assert!(BigUint::random_bits(0, &mut rng).is_zero());
+    for &n in &[1, 63, 64, 65, 200, 1000] {
+        let mut seen_top = false;
+        for _ in 0..100 {
+            let x = BigUint::random_bits(n, &mut rng);
+            assert!(x.bits() <= n);
+            seen_top |= x.bits() == n;
+        }
+        assert!(seen_top, "{} bits", n);
+    }
+}
+
+#[test]
+fn random_below_is_in_range_and_unbiased() {
+    let mut rng = XorShift(0x3243F6A8885A308D);
+    for _ in 0..100 {
+        let len = 1 + (rng.next() % 4) as usize;
+        let bound = &rng.biguint(len) >> (rng.next() % 64) as usize;
+        let bound = if bound.is_zero() { BigUint::from_u64(1) } else { bound };
+        for _ in 0..10 {
+            assert!(BigUint::random_below(&bound, &mut rng) < bound);
+        }
+    }
+
+    // a bound just above a power of two is where a modulo would be most
+    // skewed; each value of 0..5 should come up about 20000 times
+    let mut rng = Xoshiro256::new(2);
+    let mut counts = [0; 5];
+    for _ in 0..100_000 {
+        let x = BigUint::random_below(&BigUint::from_u64(5), &mut rng);
+        counts[x.limbs()[0] as usize] += 1;
+    }
+    for &c in &counts {
+        assert!((19_000..21_000).contains(&c), "{:?}", counts);
+    }
+}
+
+#[test]
+fn random_ranges() {
+    let mut rng = Xoshiro256::new(3);
+    let (lo, hi) = (BigUint::from_u64(1) << 100, (BigUint::from_u64(1) << 100) + 3);
+    for _ in 0..100 {
+        let x = BigUint::random_range(&lo, &hi, &mut rng);
+        assert!(lo <= x && x < hi);
+    }
+    let (lo, hi) = (BigInt::from(-10), BigInt::from(10));
+    let mut seen = [false; 20];
+    for _ in 0..1000 {
+        let x = i64::try_from(BigInt::random_range(&lo, &hi, &mut rng)).unwrap();
+        seen[(x + 10) as usize] = true;
+    }
+    assert!(seen.iter().all(|&s| s));
+}
+
+#[test]
+#[should_panic]
+fn empty_range_panics() {
+    let n = BigUint::from_u64(5);
+    BigUint::random_range(&n, &n, &mut Xoshiro256::new(0));
+}
+
+#[test]
+fn random_primes_have_the_requested_size() {
+    let mut rng = Xoshiro256::new(4);
+    for &bits in &[2, 3, 10, 64, 65, 256] {
+        let p = BigUint::random_prime(bits, &mut rng);
+        assert_eq!(p.bits(), bits);
+        assert!(p.is_prime());
+    }
+}
@@ -0,0 +1,184 @@
+extern crate bignum;
+use bignum::{BigUint,BigInt,BigRational,BigNumError};
+
+mod common;
+use common::XorShift;
+
+fn int(n: i64) -> BigInt {
+    BigInt::from_i64(n)
+}
+
+fn ratio(num: i64, den: i64) -> BigRational {
+    BigRational::new(int(num), int(den))
+}
+
+fn random_ratio(rng: &mut XorShift) -> (i64, i64) {
+    let num = (rng.next() % 2001) as i64 - 1000;
+    let den = (rng.next() % 999) as i64 + 1;
+    (num, if rng.next().is_multiple_of(2) { den } else { -den })
+}
+
+#[test]
+fn values_are_reduced() {
+    let r = ratio(6, -4);
+    assert_eq!(r.numer(), &int(-3));
+    assert_eq!(r.denom(), &int(2));
+    assert_eq!(ratio(0, -5).denom(), &int(1));
+    assert_eq!(ratio(10, 5), BigRational::from_integer(int(2)));
+    assert!(ratio(10, 5).is_integer());
+    assert!(!ratio(-1, 3).abs().is_negative());
+}
+
+#[test]
+#[should_panic]
+fn zero_denominator_panics() {
+    ratio(1, 0);
+}
+
+#[test]
+fn arithmetic_matches_cross_multiplication() {
+    let mut rng = XorShift(0xC19BF174CF692694);
+    for _ in 0..500 {
+        let ((a, b), (c, d)) = (random_ratio(&mut rng), random_ratio(&mut rng));
+        let (x, y) = (ratio(a, b), ratio(c, d));
+        assert_eq!(&x + &y, ratio(a * d + c * b, b * d));
+        assert_eq!(&x - &y, ratio(a * d - c * b, b * d));
+        assert_eq!(&x * &y, ratio(a * c, b * d));
+        if c != 0 {
+            assert_eq!(&x / &y, ratio(a * d, b * c));
+            assert_eq!(y.recip(), ratio(d, c));
+        }
+        assert_eq!(x.cmp(&y), ((a * d) * (b * d).signum()).cmp(&((c * b) * (b * d).signum())));
+
+        let mut z = x.clone();
+        z += &y;
+        z -= y.clone();
+        assert_eq!(z, x);
+        assert_eq!(-(-&x), x);
+    }
+}
+
+#[test]
+fn powers() {
+    assert_eq!(ratio(-2, 3).pow(3), ratio(-8, 27));
+    assert_eq!(ratio(-2, 3).pow(-2), ratio(9, 4));
+    assert_eq!(ratio(5, 7).pow(0), ratio(1, 1));
+}
+
+#[test]
+fn rounding() {
+    let cases = [
+        // value, floor, ceil, trunc, round
+        ((7, 2), 3, 4, 3, 4),
+        ((-7, 2), -4, -3, -3, -4),
+        ((5, 3), 1, 2, 1, 2),
+        ((-5, 3), -2, -1, -1, -2),
+        ((4, 3), 1, 2, 1, 1),
+        ((-4, 3), -2, -1, -1, -1),
+        ((6, 1), 6, 6, 6, 6),
+        ((-1, 2), -1, 0, 0, -1),
+    ];
+    for &((n, d), floor, ceil, trunc, round) in &cases {
+        let r = ratio(n, d);
+        assert_eq!(r.floor(), int(floor), "floor({}/{})", n, d);
+        assert_eq!(r.ceil(), int(ceil), "ceil({}/{})", n, d);
+        assert_eq!(r.trunc(), int(trunc), "trunc({}/{})", n, d);
+        assert_eq!(r.round(), int(round), "round({}/{})", n, d);
+    }
+}
+
+#[test]
+fn from_f64_is_exact() {
+    assert_eq!(BigRational::from_f64(0.5), Some(ratio(1, 2)));
+    assert_eq!(BigRational::from_f64(-6.25), Some(ratio(-25, 4)));
+    assert_eq!(BigRational::from_f64(3.0), Some(ratio(3, 1)));
+    assert_eq!(BigRational::from_f64(f64::NAN), None);
+    assert_eq!(BigRational::from_f64(f64::INFINITY), None);
+
+    // 0.1 is not a tenth in binary
+    let tenth = BigRational::from_f64(0.1).unwrap();
+    assert!(tenth != ratio(1, 10));
+    assert_eq!(tenth.denom(), &BigInt::from(1u64 << 55));
+
+    let tiny = BigRational::from_f64(f64::from_bits(1)).unwrap();
+    assert_eq!(tiny.numer(), &int(1));
+    assert_eq!(tiny.denom().magnitude().bits(), 1075);
+
+    let max = BigRational::from_f64(f64::MAX).unwrap();
+    assert!(max.is_integer());
+    assert_eq!(max.numer().to_f64(), f64::MAX);
+}
+
+#[test]
+fn to_f64_rounds_to_nearest() {
+    assert_eq!(ratio(1, 2).to_f64(), 0.5);
+    assert_eq!(ratio(-1, 3).to_f64(), -1.0 / 3.0);
+    assert_eq!(ratio(2, 3).to_f64(), 2.0 / 3.0);
+    assert_eq!(ratio(0, 1).to_f64(), 0.0);
+    assert_eq!("36.6".parse::<BigRational>().unwrap().to_f64(), 36.6);
+    for &x in &[0.1, -1e300, 5e-300, f64::MAX] {
+        assert_eq!(BigRational::from_f64(x).unwrap().to_f64(), x);
+    }
+    assert_eq!("1e400".parse::<BigRational>().unwrap().to_f64(), f64::INFINITY);
+}
+
+#[test]
+fn formatting() {
+    assert_eq!(ratio(-3, 4).to_string(), "-3/4");
+    assert_eq!(ratio(8, 4).to_string(), "2");
+    assert_eq!(format!("{:>6}", ratio(1, 3)), "   1/3");
+    assert_eq!(format!("{:.3}", ratio(2, 3)), "0.667");
+    assert_eq!(format!("{:.2}", ratio(-1, 8)), "-0.13");
+    assert_eq!(format!("{:.0}", ratio(5, 2)), "3");
+    assert_eq!(format!("{:+.1}", ratio(1, 20)), "+0.1");
+    assert_eq!(format!("{:08.2}", ratio(-314, 100)), "-0003.14");
+    assert_eq!(ratio(-1, 1000).to_decimal_string(2), "0.00");
+    assert_eq!(ratio(123, 1).to_decimal_string(2), "123.00");
+}
+
+#[test]
+fn repeating_decimals() {
+    assert_eq!(ratio(1, 4).to_repeating_decimal(50), "0.25");
+    assert_eq!(ratio(1, 3).to_repeating_decimal(50), "0.(3)");
+    assert_eq!(ratio(1, 6).to_repeating_decimal(50), "0.1(6)");
+    assert_eq!(ratio(-22, 7).to_repeating_decimal(50), "-3.(142857)");
+    assert_eq!(ratio(1, 12).to_repeating_decimal(50), "0.08(3)");
+    assert_eq!(ratio(7, 1).to_repeating_decimal(50), "7");
+    assert_eq!(ratio(1, 97).to_repeating_decimal(10), "0.0103092783...");
+    assert_eq!(ratio(1, 7).to_repeating_decimal(6), "0.(142857)");
+}
+
+#[test]
+fn parsing() {
+    assert_eq!("3/-4".parse::<BigRational>().ok(), None);
+    assert_eq!("-6/8".parse::<BigRational>(), Ok(ratio(-3, 4)));
+    assert_eq!("+42".parse::<BigRational>(), Ok(ratio(42, 1)));
+    assert_eq!("273.15".parse::<BigRational>(), Ok(ratio(27315, 100)));
+    assert_eq!("-.5".parse::<BigRational>(), Ok(ratio(-1, 2)));
+    assert_eq!("2.".parse::<BigRational>(), Ok(ratio(2, 1)));
+    assert!("1/0".parse::<BigRational>().is_err());
+    assert!(".".parse::<BigRational>().is_err());
+    assert!("1.2.3".parse::<BigRational>().is_err());
+    assert!("".parse::<BigRational>().is_err());
+    assert!("abc".parse::<BigRational>().is_err());
+}
+
+#[test]
+fn parsing_exponents() {
+    assert_eq!("1e3".parse::<BigRational>(), Ok(ratio(1000, 1)));
+    assert_eq!("-2.5E-2".parse::<BigRational>(), Ok(ratio(-1, 40)));
+    assert_eq!("12.75e+1".parse::<BigRational>(), Ok(ratio(255, 2)));
+    assert_eq!(".5e1".parse::<BigRational>(), Ok(ratio(5, 1)));
+    assert_eq!("7e0".parse::<BigRational>(), Ok(ratio(7, 1)));
+    let tiny = "3e-100".parse::<BigRational>().unwrap();
+    assert_eq!(tiny.numer(), &int(3));
+    assert_eq!(tiny.denom(), &BigInt::from(BigUint::from_u64(10).pow(100)));
+
+    assert_eq!("1e".parse::<BigRational>(), Err(BigNumError::InvalidExponent));
+    assert_eq!("1e1.5".parse::<BigRational>(), Err(BigNumError::InvalidExponent));
+    assert_eq!("1e4294967296".parse::<BigRational>(), Err(BigNumError::InvalidExponent));
+    assert_eq!("1e-99999999999999999999".parse::<BigRational>(),
+               Err(BigNumError::InvalidExponent));
+    assert_eq!("e5".parse::<BigRational>(), Err(BigNumError::EmptyString));
+    assert!("1/2e3".parse::<BigRational>().is_err());
+}
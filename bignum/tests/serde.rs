@@ -0,0 +1,44 @@
+#![cfg(feature = "serde")]
+
+extern crate bignum;
+extern crate serde_test;
+use bignum::{BigUint,BigInt};
+
+mod common;
+use common::XorShift;
+
+use serde_test::{assert_tokens,assert_de_tokens,assert_de_tokens_error,Configure,Readable,Token};
+
+#[test]
+fn human_readable_formats_use_decimal_strings() {
+    assert_tokens(&BigUint::from(12345u32).readable(), &[Token::Str("12345")]);
+    assert_tokens(&BigInt::from(-12345).readable(), &[Token::Str("-12345")]);
+    assert_tokens(&BigInt::zero().readable(), &[Token::Str("0")]);
+    assert_de_tokens_error::<Readable<BigUint>>(&[Token::Str("-1")], "invalid digit for the given base");
+    assert_de_tokens_error::<Readable<BigInt>>(&[Token::Str("")], "cannot parse a number from an empty string");
+
+    let mut rng = XorShift(0x428A2F98D728AE22);
+    for len in 1..10 {
+        let n = rng.biguint(len);
+        // tokens only hold static strings
+        let s: &'static str = Box::leak(n.to_string().into_boxed_str());
+        assert_tokens(&n.clone().readable(), &[Token::Str(s)]);
+        let s: &'static str = Box::leak(format!("-{}", n).into_boxed_str());
+        assert_tokens(&(-BigInt::from(n)).readable(), &[Token::Str(s)]);
+    }
+}
+
+#[test]
+fn binary_formats_use_bytes() {
+    assert_tokens(&BigUint::from(0x0102u16).compact(), &[Token::Bytes(&[2, 1])]);
+    assert_tokens(&BigInt::from(-129).compact(), &[Token::Bytes(&[0x7F, 0xFF])]);
+    assert_tokens(&BigInt::from(128).compact(), &[Token::Bytes(&[0x80, 0x00])]);
+
+    // formats without a bytes type hand over a sequence instead
+    assert_de_tokens(&BigUint::from(0x0102u16).compact(), &[
+        Token::Seq { len: Some(2) }, Token::U8(2), Token::U8(1), Token::SeqEnd,
+    ]);
+    assert_de_tokens(&BigInt::from(-1).compact(), &[
+        Token::Seq { len: Some(1) }, Token::U8(0xFF), Token::SeqEnd,
+    ]);
+}
@@ -0,0 +1,180 @@
+extern crate bignum;
+use bignum::{BigUint,BigNumError,UInt,U256,U512};
+
+mod common;
+use common::XorShift;
+
+use std::convert::TryFrom;
+
+const ROUNDS: usize = 500;
+
+// random values with runs of zero and all-ones limbs, so the carries and
+// the overflow checks get exercised
+fn value<const N: usize>(rng: &mut XorShift) -> UInt<N> {
+    let mut limbs = [0u64; N];
+    let top = (rng.next() % (N as u64 + 1)) as usize;
+    for x in limbs[..top].iter_mut() {
+        *x = match rng.next() % 4 {
+            0 => 0,
+            1 => !0,
+            _ => rng.next(),
+        };
+    }
+    UInt::from_limbs(limbs)
+}
+
+fn big<const N: usize>(x: UInt<N>) -> BigUint {
+    BigUint::from(x)
+}
+
+fn modulus<const N: usize>() -> BigUint {
+    &BigUint::from_u64(1) << UInt::<N>::BITS
+}
+
+fn check_against_biguint<const N: usize>(seed: u64) {
+    let mut rng = XorShift(seed);
+    let m = modulus::<N>();
+    for _ in 0..ROUNDS {
+        let (a, b) = (value::<N>(&mut rng), value::<N>(&mut rng));
+        let (x, y) = (big(a), big(b));
+
+        let sum = &x + &y;
+        assert_eq!(big(a.wrapping_add(b)), &sum % &m);
+        assert_eq!(a.overflowing_add(b).1, sum >= m);
+        assert_eq!(a.checked_add(b).is_ok(), sum < m);
+
+        assert_eq!(big(a.wrapping_sub(b)), (&(&x + &m) - &y) % &m);
+        assert_eq!(a.overflowing_sub(b).1, x < y);
+        match a.checked_sub(b) {
+            Ok(d) => assert_eq!(big(d), &x - &y),
+            Err(e) => assert_eq!(e, BigNumError::Negative),
+        }
+
+        let product = &x * &y;
+        assert_eq!(big(a.wrapping_mul(b)), &product % &m);
+        assert_eq!(a.overflowing_mul(b).1, product >= m);
+        let (lo, hi) = a.widening_mul(b);
+        assert_eq!(&(&big(hi) << UInt::<N>::BITS) + &big(lo), product);
+
+        if b.is_zero() {
+            assert_eq!(a.checked_div_rem(b), Err(BigNumError::DivisionByZero));
+        } else {
+            let (q, r) = a.div_rem(b);
+            let (bq, br) = x.divmod(&y);
+            assert_eq!((big(q), big(r)), (bq, br));
+            assert_eq!(a / b, q);
+            assert_eq!(a % b, r);
+        }
+
+        let s = (rng.next() % UInt::<N>::BITS as u64) as usize;
+        assert_eq!(big(a << s), (&x << s) % &m);
+        assert_eq!(big(a >> s), &x >> s);
+        assert_eq!(a.cmp(&b), x.cmp(&y));
+        assert_eq!(a.bits(), x.highest_bit());
+        assert_eq!(big(a.wrapping_neg()), (&m - &x) % &m);
+        assert_eq!(big(!a), &(&m - &x) - &BigUint::from_u64(1));
+        assert_eq!(big(a & b) | big(a ^ b), big(a | b));
+    }
+}
+
+#[test]
+fn u256_matches_biguint() {
+    check_against_biguint::<4>(0x6A09E667F3BCC908);
+}
+
+#[test]
+fn u512_matches_biguint() {
+    check_against_biguint::<8>(0xBB67AE8584CAA73B);
+}
+
+#[test]
+fn odd_widths_match_biguint() {
+    check_against_biguint::<1>(0x3C6EF372FE94F82B);
+    check_against_biguint::<3>(0xA54FF53A5F1D36F1);
+}
+
+#[test]
+fn conversions_are_lossless() {
+    let mut rng = XorShift(0x510E527FADE682D1);
+    for _ in 0..ROUNDS {
+        let a = value::<8>(&mut rng);
+        assert_eq!(U512::try_from(big(a)), Ok(a));
+        assert_eq!(U512::try_from(&big(a)).unwrap(), a);
+    }
+    let too_big = &BigUint::from_u64(1) << 256;
+    assert_eq!(U256::try_from(&too_big), Err(BigNumError::OutOfRange));
+    assert_eq!(U256::try_from(&too_big - &BigUint::from_u64(1)), Ok(U256::MAX));
+    assert_eq!(U256::try_from(BigUint::from_u64(0)), Ok(U256::ZERO));
+    assert_eq!(U256::from(7u64), U256::from_u64(7));
+    assert_eq!(U256::default(), U256::ZERO);
+}
+
+#[test]
+fn constants_and_formatting() {
+    assert_eq!(U256::BITS, 256);
+    assert_eq!(U256::MAX.bits(), 256);
+    assert_eq!(U256::ZERO.leading_zeros(), 256);
+    assert_eq!(U256::one().leading_zeros(), 255);
+    assert!(U256::MAX.test_bit(255) && !U256::MAX.test_bit(256));
+    assert_eq!(U256::MAX.to_string(), big(U256::MAX).to_string());
+    assert_eq!(format!("{:?}", U256::from_u64(42)), "42");
+    assert_eq!(format!("{:#x}", U256::from_u64(255) << 64), "0xff0000000000000000");
+    assert_eq!(format!("{:>5}", U256::from_u64(9)), "    9");
+}
+
+#[test]
+fn checked_errors() {
+    assert_eq!(U256::MAX.checked_add(U256::one()), Err(BigNumError::OutOfRange));
+    assert_eq!(U256::ZERO.checked_sub(U256::one()), Err(BigNumError::Negative));
+    assert_eq!((U256::one() << 128).checked_mul(U256::one() << 128), Err(BigNumError::OutOfRange));
+    assert_eq!((U256::one() << 127).checked_mul(U256::one() << 128), Ok(U256::one() << 255));
+    assert_eq!(U256::one().checked_div(U256::ZERO), Err(BigNumError::DivisionByZero));
+    assert_eq!(U256::one().checked_rem(U256::ZERO), Err(BigNumError::DivisionByZero));
+}
+
+#[test]
+fn is_copy_in_loops() {
+    // a Fibonacci-style recurrence modulo 2^256, against BigUint
+    let (mut a, mut b) = (U256::ZERO, U256::one());
+    let (mut x, mut y) = (BigUint::from_u64(0), BigUint::from_u64(1));
+    let m = modulus::<4>();
+    for _ in 0..1000 {
+        let next = a.wrapping_add(b);
+        a = b;
+        b = next;
+        let next = &(&x + &y) % &m;
+        x = y;
+        y = next;
+    }
+    assert_eq!(big(b), y);
+}
+
+#[test]
+#[should_panic(expected = "attempt to add with overflow")]
+fn add_overflow_panics() {
+    let _ = U256::MAX + U256::one();
+}
+
+#[test]
+#[should_panic(expected = "attempt to subtract with overflow")]
+fn sub_overflow_panics() {
+    let _ = U256::ZERO - U256::one();
+}
+
+#[test]
+#[should_panic(expected = "attempt to multiply with overflow")]
+fn mul_overflow_panics() {
+    let _ = U256::MAX * U256::from_u64(2);
+}
+
+#[test]
+#[should_panic(expected = "attempt to divide by zero")]
+fn div_by_zero_panics() {
+    let _ = U512::one() / U512::ZERO;
+}
+
+#[test]
+#[should_panic(expected = "attempt to shift left with overflow")]
+fn shift_overflow_panics() {
+    let _ = U256::one() << 256;
+}
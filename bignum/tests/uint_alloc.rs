@@ -0,0 +1,77 @@
+// UInt promises never to allocate; this binary counts every allocation
+// made on the test's thread, so it has a file of its own
+extern crate bignum;
+use bignum::{UInt,U256,U512};
+
+mod common;
+use common::XorShift;
+
+use std::alloc::{GlobalAlloc,Layout,System};
+use std::cell::Cell;
+use std::hint::black_box;
+
+struct Counting;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|n| n.set(n.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+fn allocations() -> usize {
+    ALLOCATIONS.with(|n| n.get())
+}
+
+fn values<const N: usize>(rng: &mut XorShift, count: usize) -> Vec<UInt<N>> {
+    (0..count).map(|_| {
+        let mut limbs = [0u64; N];
+        let top = (rng.next() % (N as u64 + 1)) as usize;
+        for x in limbs[..top].iter_mut() {
+            *x = rng.next();
+        }
+        UInt::from_limbs(limbs)
+    }).collect()
+}
+
+// the operations on each pair of neighbours, divisions of every size
+// included, folded into one value
+fn exercise<const N: usize>(xs: &[UInt<N>]) -> UInt<N> {
+    let mut acc = UInt::<N>::ZERO;
+    for pair in xs.windows(2) {
+        let (a, b) = (pair[0], pair[1]);
+        acc = acc.wrapping_add(a.wrapping_mul(b)) ^ a.wrapping_sub(b);
+        acc = acc.wrapping_add(a.widening_mul(b).1);
+        if let Ok((q, r)) = a.checked_div_rem(b) {
+            acc = acc.wrapping_add(q) ^ r;
+        }
+        if let Ok(s) = a.checked_add(b) {
+            acc = acc ^ (s >> 3) ^ (s << 5);
+        }
+    }
+    acc
+}
+
+#[test]
+fn arithmetic_does_not_allocate() {
+    let mut rng = XorShift(0x6A09E667F3BCC908);
+    let small: Vec<UInt<2>> = values(&mut rng, 300);
+    let mid: Vec<U256> = values(&mut rng, 300);
+    let large: Vec<U512> = values(&mut rng, 300);
+
+    let before = allocations();
+    black_box((exercise(&small), exercise(&mid), exercise(&large)));
+    let after = allocations();
+    assert_eq!(after - before, 0, "UInt arithmetic allocated");
+}